@@ -0,0 +1,36 @@
+use pretty_tree::*;
+
+fn is_leaf(tree: &PrettyTree, name: &str) -> bool {
+    matches!(tree, PrettyTree::Value(x) if x == name)
+}
+
+fn main() {
+    let tree = PrettyTree::branch_of("Program", [
+        PrettyTree::branch_of("Let", [
+            PrettyTree::leaf("x"),
+            PrettyTree::branch_of("Add", [PrettyTree::leaf("1"), PrettyTree::leaf("2")]),
+        ]),
+        PrettyTree::branch_of("Let", [
+            PrettyTree::leaf("y"),
+            PrettyTree::branch_of("Mul", [PrettyTree::leaf("x"), PrettyTree::leaf("3")]),
+        ]),
+        PrettyTree::branch_of("Print", [
+            PrettyTree::branch_of("Add", [PrettyTree::leaf("x"), PrettyTree::leaf("y")]),
+        ]),
+    ]);
+    println!("{}\n", tree.render());
+
+    let path = tree.path_to(|x| is_leaf(x, "y")).unwrap_or_default();
+    let labels = path.iter().map(|x| match x {
+        PrettyTree::Branch(x) => x.label.to_string(),
+        x => x.to_string(),
+    });
+    println!("path to y: {}\n", labels.collect::<Vec<_>>().join(" › "));
+
+    let name = std::env::args().nth(1).unwrap_or_else(|| String::from("x"));
+    let predicate = move |x: &PrettyTree| is_leaf(x, &name);
+    let formatter = Formatter::default().focus(Some(Focus::ancestry(predicate.clone())));
+    println!("{}\n", tree.format(&formatter));
+    let formatter = Formatter::default().focus(Some(Focus::inverted(predicate)));
+    println!("{}", tree.format(&formatter));
+}
@@ -0,0 +1,23 @@
+use pretty_tree::*;
+
+fn main() {
+    let literal = PrettyTree::branch_of("Expr", [
+        PrettyTree::branch_of("Paren", [PrettyTree::branch_of("Expr", [PrettyTree::leaf("Literal 5")])]),
+    ]);
+    let call = PrettyTree::branch_of("Expr", [
+        PrettyTree::branch_of("Call", [
+            PrettyTree::leaf("print"),
+            PrettyTree::branch_of("Expr", [PrettyTree::branch_of("Unary", [
+                PrettyTree::branch_of("Neg", [PrettyTree::branch_of("Expr", [
+                    PrettyTree::branch_of("Paren", [PrettyTree::branch_of("Expr", [PrettyTree::leaf("x")])]),
+                ])]),
+            ])]),
+        ]),
+    ]);
+    let tree = PrettyTree::branch_of("Program", [literal, call]);
+    println!("{}\n", tree.render());
+    let formatter = Formatter::default().map_formatter_style(|x| x.fold_chains(Some(usize::MAX)));
+    println!("{}\n", tree.format(&formatter));
+    let formatter = Formatter::default().map_formatter_style(|x| x.fold_chains(Some(3)).chain_separator(" / "));
+    println!("{}", tree.format(&formatter));
+}
@@ -0,0 +1,43 @@
+use std::collections::BTreeMap;
+use std::net::Ipv4Addr;
+use std::time::Duration;
+
+use pretty_tree::*;
+
+#[derive(Debug)]
+#[allow(dead_code)]
+enum Shape {
+    Circle { radius: f32 },
+    Rect(u32, u32),
+    Empty,
+}
+
+#[derive(Debug)]
+#[allow(dead_code)]
+struct Scene {
+    name: String,
+    shapes: Vec<Shape>,
+    tags: BTreeMap<&'static str, Option<u8>>,
+    origin: (i32, i32),
+    timeout: Duration,
+    hidden: Vec<Shape>,
+}
+
+fn main() {
+    let scene = Scene {
+        name: "demo \"scene\"".to_string(),
+        shapes: vec![Shape::Circle { radius: 1.5 }, Shape::Rect(2, 3), Shape::Empty],
+        tags: BTreeMap::from([("layer", Some(2)), ("group", None)]),
+        origin: (0, -4),
+        timeout: Duration::from_millis(250),
+        hidden: Vec::default(),
+    };
+    from_debug(&scene).print_pretty_tree();
+
+    let host = Ipv4Addr::LOCALHOST;
+    let tree = branch_of("leaves", [
+        PrettyTree::from(DisplayLeaf(host)),
+        PrettyTree::from(DebugLeaf(Shape::Rect(1, 1))),
+    ]);
+    tree.print_pretty_tree();
+}
@@ -0,0 +1,13 @@
+use pretty_tree::fs::{dir_tree, DirTreeOptions};
+use pretty_tree::*;
+
+fn main() -> std::io::Result<()> {
+    let path = std::env::args().nth(1).unwrap_or_else(|| String::from("."));
+    let options = DirTreeOptions::default()
+        .exclude("target/")
+        .exclude("*.png")
+        .max_depth(Some(2))
+        .sizes(true);
+    dir_tree(path, &options)?.print_pretty_tree();
+    Ok(())
+}
@@ -22,15 +22,15 @@ impl DocumentSection {
 }
 
 impl ToPrettyTree for DocumentSection {
-    fn to_pretty_tree(&self) -> PrettyTree {
+    fn to_pretty_tree(&self) -> PrettyTree<'_> {
         let children = self.subsections
             .iter()
             .map(|x| x.to_pretty_tree())
             .collect::<Vec<_>>();
         if children.is_empty() {
-            return PrettyTree::value(&self.title)
+            return PrettyTree::leaf(&self.title)
         }
-        PrettyTree::branch_of( &self.title, &children )
+        PrettyTree::branch_of( &self.title, children )
     }
 }
 
@@ -31,7 +31,7 @@ enum UnaryOperator {
 }
 
 impl ToPrettyTree for AstNode {
-    fn to_pretty_tree(&self) -> PrettyTree {
+    fn to_pretty_tree(&self) -> PrettyTree<'_> {
         match self {
             AstNode::BinaryOperation { operator, left, right } => {
                 let op_label = match operator {
@@ -42,7 +42,7 @@ impl ToPrettyTree for AstNode {
                 };
                 PrettyTree::branch_of(
                     op_label,
-                    &[
+                    [
                         left.as_ref().to_pretty_tree(),
                         right.as_ref().to_pretty_tree(),
                     ],
@@ -52,7 +52,7 @@ impl ToPrettyTree for AstNode {
                 let op_label = match operator {
                     UnaryOperator::Negate => "Negate",
                 };
-                PrettyTree::branch_of(op_label, &[operand.as_ref().to_pretty_tree()])
+                PrettyTree::branch_of(op_label, [operand.as_ref().to_pretty_tree()])
             }
             AstNode::Number(n) => PrettyTree::string(n.to_string()),
         }
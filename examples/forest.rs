@@ -0,0 +1,26 @@
+use pretty_tree::*;
+
+fn main() {
+    let tree = |label: &'static str| PrettyTree::branch_of(label, [PrettyTree::leaf("a"), PrettyTree::leaf("b")]);
+    let fragment = |nodes: Vec<PrettyTree<'static>>| PrettyTree::Fragment(PrettyFragment::from_iter(nodes));
+
+    let forest = fragment(vec![tree("first"), tree("second"), PrettyTree::leaf("third")]);
+    println!("-- a forest\n{}", forest.render());
+    let formatter = Formatter::default().map_formatter_style(|x| x.forest_separator(Some("")));
+    println!("-- separated by blank lines\n{}", forest.format(&formatter));
+    let formatter = Formatter::default().map_formatter_style(|x| x.forest_separator(Some("---")));
+    println!("-- separated by ---\n{}", forest.format(&formatter));
+
+    println!("-- one tree\n{}", fragment(vec![tree("only")]).render());
+    println!("-- no trees\n{}", fragment(Vec::default()).render());
+
+    let nested = PrettyTree::branch_of("parent", [
+        PrettyTree::leaf("before"),
+        fragment(vec![PrettyTree::leaf("spliced"), fragment(vec![tree("nested")])]),
+        fragment(Vec::default()),
+        PrettyTree::leaf("after"),
+    ]);
+    println!("-- fragments among children\n{}", nested.render());
+    let forest = fragment(vec![fragment(vec![tree("first"), tree("second")]), tree("third")]);
+    println!("-- fragments in a forest\n{}", forest.render());
+}
@@ -0,0 +1,32 @@
+use pretty_tree::*;
+
+fn main() {
+    let tree = PrettyTree::branch_of("Program", [
+        PrettyTree::branch_of("Let", [
+            PrettyTree::leaf("x"),
+            PrettyTree::branch_of("Add", [PrettyTree::leaf("1"), PrettyTree::leaf("2")]),
+        ]),
+        PrettyTree::list_of::<Vec<&str>, _>(["a", "b"]),
+        PrettyTree::branch_of("Doc", [PrettyTree::leaf("first line\nsecond line")]),
+        PrettyTree::branch_of("Print", [PrettyTree::leaf("x")]),
+    ]);
+    for gutter in [Gutter::LineNumbers, Gutter::NodeIndex, Gutter::Path] {
+        let formatter = Formatter::default().map_formatter_style(|x| x.gutter(Some(gutter)));
+        println!("-- {gutter:?}\n{}\n", tree.format(&formatter));
+    }
+    for path in ["0.0.1.1", "0.1.0", "0.3"] {
+        let node = tree.get_by_path(path).unwrap_or_default();
+        println!("{path}: {}", node.log_display());
+    }
+
+    let forest = PrettyTree::Fragment(PrettyFragment::from_iter([
+        PrettyTree::branch_of("first", [PrettyTree::leaf("a")]),
+        PrettyTree::branch_of("second", [PrettyTree::leaf("b")]),
+    ]));
+    let formatter = Formatter::default()
+        .map_formatter_style(|x| x.gutter(Some(Gutter::Path)).forest_separator(Some("")));
+    println!("\n{}", forest.format(&formatter));
+    let formatter = Formatter::default()
+        .map_formatter_style(|x| x.gutter(Some(Gutter::Path)).fold_chains(Some(usize::MAX)));
+    println!("\n{}", tree.format(&formatter));
+}
@@ -75,14 +75,14 @@ impl HtmlTree {
 }
 
 impl ToPrettyTree for HtmlTree {
-    fn to_pretty_tree(&self) -> pretty_tree::PrettyTree {
+    fn to_pretty_tree(&self) -> pretty_tree::PrettyTree<'_> {
         match self {
             HtmlTree::Element { name, children } => {
                 let children = children
                     .iter()
                     .map(|x| x.to_pretty_tree())
                     .collect::<Vec<_>>();
-                PrettyTree::branch_of(name, &children)
+                PrettyTree::branch_of(name, children)
             }
             HtmlTree::Text(value) => {
                 PrettyTree::str(value)
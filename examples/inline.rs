@@ -0,0 +1,21 @@
+use pretty_tree::*;
+
+fn main() {
+    let point = |x: i32, y: i32| PrettyBranch::new("Point").with_field("x", x).with_field("y", y);
+    let tree = PrettyBranch::new("Program")
+        .with_child(PrettyTree::branch_of("Add", [1, 2]))
+        .with_child(PrettyTree::branch_of("Mul", [
+            PrettyTree::branch_of("Add", [1, 2]),
+            PrettyTree::branch_of("Neg", [3]),
+        ]))
+        .with_child(point(3, -4))
+        .with_child(PrettyTree::list_of::<Vec<u8>, _>([1u8, 2, 3]))
+        .with_child(PrettyTree::branch_of("Call", [
+            PrettyTree::leaf("print"),
+            PrettyTree::string("a long string argument that does not fit on the line"),
+        ]))
+        .with_child(PrettyTree::branch_of("Block", (0..10).map(|x| PrettyTree::branch_of("Stmt", [x]))));
+    let tree = PrettyTree::from(tree);
+    let formatter = Formatter::default().map_formatter_style(|x| x.inline_width(Some(40)));
+    println!("{}", tree.format(&formatter));
+}
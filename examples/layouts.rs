@@ -0,0 +1,22 @@
+use pretty_tree::*;
+
+fn main() {
+    let tree = PrettyTree::branch_of("Add", [
+        PrettyTree::branch_of("Mul", [PrettyTree::leaf("2"), PrettyTree::leaf("x")]),
+        PrettyTree::branch_of("Neg", [PrettyTree::branch_of("Call", [
+            PrettyTree::leaf("sqrt"),
+            PrettyTree::leaf("y"),
+            PrettyTree::leaf("1"),
+        ])]),
+        PrettyTree::leaf("1"),
+    ]);
+    for layout in [Layout::Indented, Layout::TopDown, Layout::LeftToRight] {
+        for glyphs in [GlyphStyle::Square, GlyphStyle::Ascii] {
+            let formatter = Formatter::default().map_formatter_style(|x| x.layout(layout).glyphs(glyphs));
+            println!("-- {layout:?}, {glyphs:?}\n{}\n", tree.format(&formatter));
+        }
+    }
+    let wide = PrettyTree::branch_of("a very long label", [PrettyTree::leaf("1"), PrettyTree::leaf("2")]);
+    let formatter = Formatter::default().map_formatter_style(|x| x.layout(Layout::TopDown));
+    println!("{}", wide.format(&formatter));
+}
@@ -0,0 +1,32 @@
+use pretty_tree::*;
+
+/// The Collatz predecessors of a number; an infinite tree.
+struct Collatz(u64);
+
+impl PrettyTreeSource for Collatz {
+    fn label(&self) -> String {
+        self.0.to_string()
+    }
+    fn children(&self) -> Box<dyn Iterator<Item = Box<dyn PrettyTreeSource + '_>> + '_> {
+        let n = self.0;
+        let odd = (n > 4 && n % 6 == 4).then(|| (n - 1) / 3);
+        let children = std::iter::once(n * 2).chain(odd).map(|x| Box::new(Collatz(x)) as Box<dyn PrettyTreeSource>);
+        Box::new(children)
+    }
+}
+
+fn main() {
+    let formatter = Formatter::default().map_formatter_style(|style| {
+        style.max_depth(Some(8)).max_children(Some(2))
+    });
+    println!("Lazy source:");
+    println!("{}", formatter.format_source(&Collatz(1)));
+    println!("--------------------------");
+    let tree = PrettyTree::branch_of("range", 0..10u32);
+    let formatter = formatter.map_formatter_style(|style| style.max_children(Some(3)));
+    println!("Limits on a regular tree:");
+    println!("{}", tree.format(&formatter));
+    println!("--------------------------");
+    println!("ToPrettyTree values are sources too:");
+    println!("{}", formatter.format_source(&vec![vec![1u32, 2, 3, 4], vec![5]]));
+}
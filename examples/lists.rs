@@ -0,0 +1,22 @@
+use pretty_tree::*;
+
+fn main() {
+    let labeled = |items: &[i32]| PrettyTree::from(PrettyList::from_iter(Some("Vec<i32>"), items.iter().copied()));
+    let unlabeled = |items: &[i32]| PrettyTree::from(PrettyList::from_iter(None::<&str>, items.iter().copied()));
+    let cases = [
+        ("labeled, empty", labeled(&[])),
+        ("labeled, one element", labeled(&[1])),
+        ("labeled, several elements", labeled(&[1, 2, 3])),
+        ("unlabeled, empty", unlabeled(&[])),
+        ("unlabeled, one element", unlabeled(&[1])),
+        ("unlabeled, several elements", unlabeled(&[1, 2, 3])),
+    ];
+    for compact_mode in [false, true] {
+        let formatter = Formatter::default().map_formatter_style(|x| x.compact_mode(compact_mode));
+        println!("== compact_mode({compact_mode}) ==");
+        for (name, list) in cases.iter() {
+            let nested = PrettyTree::branch_of("Parent", [list.clone(), PrettyTree::leaf("sibling")]);
+            println!("-- {name}\n{}\n{}", list.format(&formatter), nested.format(&formatter));
+        }
+    }
+}
@@ -0,0 +1,34 @@
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use pretty_tree::*;
+
+struct StderrLogger;
+
+impl Log for StderrLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= Level::Info
+    }
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            eprintln!("[{}] {}", record.level(), record.args());
+        }
+    }
+    fn flush(&self) {}
+}
+
+fn main() {
+    let tree = PrettyBranch::new("request")
+        .with_field("method", "GET")
+        .with_field("path", "/index.html")
+        .with_child(PrettyTree::branch_of("headers", ["accept: */*", "host: localhost"]));
+    let tree = PrettyTree::from(tree);
+
+    log::set_logger(&StderrLogger).unwrap();
+    log::set_max_level(LevelFilter::Info);
+    log_tree!(Level::Info, "handling request", tree);
+    // Not rendered, the level is disabled.
+    log_tree!(Level::Debug, "handling request", tree);
+
+    tracing_subscriber::fmt().with_ansi(false).init();
+    tracing::info!(tree = tree.tracing_value(), "handling request");
+    tracing::debug!(tree = %tree.log_display(), "not rendered, the level is disabled");
+}
@@ -0,0 +1,32 @@
+use pretty_tree::*;
+
+fn file(name: &'static str, size: f64) -> PrettyTree<'static> {
+    PrettyTree::leaf(name).with_metric(size)
+}
+
+fn main() {
+    let tree = PrettyTree::branch_of("project", [
+        PrettyTree::branch_of("src", [
+            file("lib.rs", 1_204.0),
+            file("formatter.rs", 31_877.0),
+            PrettyTree::branch_of("bin", [file("main.rs", 6_530.0)]),
+        ]),
+        PrettyTree::branch_of("examples", [file("metrics.rs", 912.0), file("layouts.rs", 1_050.0)]),
+        file("Cargo.toml", 1_467.0),
+        PrettyTree::leaf("LICENSE"),
+    ]);
+    println!("{}\n", tree.render());
+    let tree = tree.sum_metrics();
+    println!("{}\n", tree.render());
+    let formatter = Formatter::default().map_formatter_style(|x| x.metric_bars(Some(20)));
+    println!("{}\n", tree.format(&formatter));
+
+    // Self time plus the time of the children, like a flame graph.
+    let profile = PrettyTree::branch_of("main", [
+        PrettyTree::branch_of("parse", [PrettyTree::leaf("tokenize").with_metric(1.25)]).with_metric(0.5),
+        PrettyTree::leaf("render").with_metric(2.125),
+    ]);
+    let formatter = Formatter::default()
+        .map_formatter_style(|x| x.metric_bars(Some(10)).glyphs(GlyphStyle::Ascii).gutter(Some(Gutter::Path)));
+    println!("{}", profile.sum_metrics().format(&formatter));
+}
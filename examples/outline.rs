@@ -0,0 +1,34 @@
+use pretty_tree::*;
+
+const INDENTED: &str = "
+project
+    design
+        api
+        storage
+    build
+\t\tci
+";
+
+const MARKDOWN: &str = "
+# Release notes
+
+Some introduction that is not part of the outline.
+
+## Features
+- Outlines
+  - from indented text
+  - from Markdown
+- Directory trees
+
+## Fixes
+1. Stable map order
+
+```rust
+// # not a heading
+```
+";
+
+fn main() {
+    PrettyTree::from_indented(INDENTED).print_pretty_tree();
+    PrettyTree::from_markdown_outline(MARKDOWN).print_pretty_tree();
+}
@@ -0,0 +1,24 @@
+use pretty_tree::*;
+
+fn point(x: u32, y: u32) -> PrettyTree<'static> {
+    PrettyBranch::new("Point")
+        .with_field("x", x)
+        .with_field("y", y)
+        .into()
+}
+
+fn main() {
+    let mut points = vec![point(0, 0); 500];
+    points.push(point(1, 2));
+    points.extend(vec![point(0, 0); 3]);
+    let tree = PrettyTree::branch_of("points", points);
+    let formatter = Formatter::default().map_formatter_style(|style| {
+        style.collapse_repeats(true)
+    });
+    println!("Collapsed runs:");
+    println!("{}", tree.format(&formatter));
+    println!("--------------------------");
+    let formatter = formatter.map_formatter_style(|style| style.back_references(true));
+    println!("With back-references:");
+    println!("{}", tree.format(&formatter));
+}
@@ -0,0 +1,39 @@
+use std::rc::Rc;
+
+use pretty_tree::*;
+
+#[derive(Debug)]
+enum Expr {
+    Var(&'static str),
+    Add(Rc<Expr>, Rc<Expr>),
+    Mul(Rc<Expr>, Rc<Expr>),
+}
+
+fn convert(nodes: &mut SharedNodes, expr: &Rc<Expr>) -> PrettyTree<'static> {
+    nodes.get_or_insert_rc(expr, |nodes| match expr.as_ref() {
+        Expr::Var(name) => PrettyTree::value(name),
+        Expr::Add(a, b) => PrettyTree::branch_of("Add", [convert(nodes, a), convert(nodes, b)]),
+        Expr::Mul(a, b) => PrettyTree::branch_of("Mul", [convert(nodes, a), convert(nodes, b)]),
+    })
+}
+
+fn main() {
+    // (x + y) * (x + y), with the sum computed once
+    let x = Rc::new(Expr::Var("x"));
+    let y = Rc::new(Expr::Var("y"));
+    let sum = Rc::new(Expr::Add(x.clone(), y));
+    let product = Rc::new(Expr::Mul(sum.clone(), sum));
+    println!("DAG:");
+    convert(&mut SharedNodes::default(), &product).print_pretty_tree();
+    println!("--------------------------");
+
+    // A block that jumps back to itself
+    let entry = PrettyShared::default();
+    let body = PrettyShared::new(PrettyTree::branch_of("body", [
+        PrettyTree::value("i += 1"),
+        PrettyTree::shared(&entry),
+    ]));
+    entry.set(PrettyTree::branch_of("entry", [PrettyTree::shared(&body)]));
+    println!("Cycle:");
+    PrettyTree::shared(&entry).print_pretty_tree();
+}
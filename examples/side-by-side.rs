@@ -0,0 +1,23 @@
+use pretty_tree::*;
+
+fn main() {
+    let before = PrettyTree::from_indented("
+fn main
+    let x
+        add
+            1
+            2
+    call print
+        x
+    return
+");
+    let after = PrettyTree::from_indented("
+fn main
+    call print
+        3
+    return
+        unit
+");
+    let formatter = Formatter::default().map_formatter_style(|x| x.highlight_changes(true));
+    println!("{}", side_by_side(&before, &after, &formatter));
+}
@@ -0,0 +1,21 @@
+use pretty_tree::*;
+
+fn main() {
+    let tree = PrettyTree::branch_of("module", [
+        PrettyTree::branch_of("fn", [
+            PrettyTree::branch_of("block", [
+                PrettyTree::branch_of("call", [ PrettyTree::value("x"), PrettyTree::value("y") ]),
+                PrettyTree::branch_of("call", [ PrettyTree::value("x") ]),
+            ]),
+        ]),
+        PrettyTree::branch_of("fn", [ PrettyTree::value("x") ]),
+    ]);
+    let stats = tree.stats();
+    if stats.node_count > 100 {
+        println!("{}", tree.render_summary(3));
+    } else {
+        println!("{tree}");
+    }
+    println!("--------------------------");
+    println!("{}", tree.render_summary(3));
+}
@@ -0,0 +1,40 @@
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use pretty_tree::*;
+
+fn main() {
+    let parsed: Result<i64, String> = "-42".parse::<i64>().map_err(|x| x.to_string());
+    let failed: Result<i64, String> = "x".parse::<i64>().map_err(|x| x.to_string());
+    let queue = VecDeque::from([1.5f64, 2.25]);
+    let shared = Rc::new(RefCell::new(vec!['a', 'b']));
+    let locked = Mutex::new(Duration::from_millis(1500));
+    let scores = HashMap::from([("carol", 7u32), ("alice", 3), ("bob", 5)]);
+    let tree = PrettyBranch::new("std types")
+        .with_field_ref("parsed", &parsed)
+        .with_field_ref("failed", &failed)
+        .with_field_ref("queue", &queue)
+        .with_field_ref("shared", &shared)
+        .with_field_ref("locked", &locked)
+        .with_field_ref("scores", &scores)
+        .with_field("path", PathBuf::from("/tmp/out.txt"))
+        .with_field("ordering", 1.cmp(&2))
+        .with_field_ref("array", &[0u16; 3])
+        .with_field_ref("tuple", &(1u8, -1i8, 'x', "four", 5.0f32));
+    tree.print_pretty_tree();
+
+    let (bytes, map, set) = (Vec::<u8>::new(), BTreeMap::<String, u8>::new(), HashSet::<char>::new());
+    let empty = PrettyBranch::new("empty")
+        .with_child_ref(&bytes)
+        .with_child_ref(&map)
+        .with_child_ref(&set);
+    for type_headers in [TypeHeaders::Full, TypeHeaders::Short, TypeHeaders::None] {
+        let formatter = Formatter::default().map_formatter_style(|x| x.type_headers(type_headers));
+        println!("{}", PrettyTree::from(&(1u8, "two")).format(&formatter));
+        println!("{}", PrettyTree::from(empty.clone()).format(&formatter));
+    }
+}
@@ -0,0 +1,38 @@
+use pretty_tree::*;
+
+struct User {
+    name: String,
+    age: u32,
+    email: Option<String>,
+}
+
+impl ToPrettyTree for User {
+    fn to_pretty_tree(&self) -> PrettyTree<'_> {
+        let email = self.email.as_deref().unwrap_or("-");
+        PrettyBranch::new("User")
+            .with_field_ref("name", &self.name)
+            .with_field("age", self.age)
+            .with_field("email", email)
+            .into()
+    }
+}
+
+fn main() {
+    let users = vec![
+        User { name: "Ann".into(), age: 31, email: Some("ann@example.com".into()) },
+        User { name: "Bartholomew".into(), age: 4, email: None },
+        User { name: "Zoë".into(), age: 102, email: Some("zoe@example.com".into()) },
+    ];
+    let tree = PrettyBranch::new("users")
+        .with_child_ref(&users)
+        .with_child(PrettyTree::branch_of("not uniform", [
+            PrettyTree::branch_of("a", [PrettyTree::key_value("x", 1)]),
+            PrettyTree::branch_of("b", [PrettyTree::key_value("y", 2)]),
+        ]))
+        .with_child(PrettyTree::branch_of("labeled rows", [
+            PrettyTree::branch_of("first", [PrettyTree::key_value("x", 1), PrettyTree::key_value("y", 2)]),
+            PrettyTree::branch_of("second", [PrettyTree::key_value("x", 10), PrettyTree::key_value("y", 20)]),
+        ]));
+    let formatter = Formatter::default().map_formatter_style(|x| x.tables(true));
+    println!("{}", PrettyTree::from(tree).format(&formatter));
+}
@@ -0,0 +1,27 @@
+use pretty_tree::*;
+use tracing::{info, info_span, warn};
+use tracing_subscriber::layer::SubscriberExt;
+
+fn lookup(key: &str) -> Option<u32> {
+    let _span = info_span!("cache lookup", key).entered();
+    let hit = key.starts_with("user:");
+    info!(hit, "checked cache");
+    hit.then_some(42)
+}
+
+fn main() {
+    let subscriber = tracing_subscriber::registry().with(PrettyTreeLayer::new());
+    tracing::subscriber::set_global_default(subscriber).unwrap();
+
+    info!("starting up");
+    let request = info_span!("request", method = "GET", path = "/users/7");
+    let _request = request.enter();
+    info!("accepted connection");
+    for key in ["user:7", "avatar"] {
+        if lookup(key).is_none() {
+            warn!(key, "cache miss");
+        }
+    }
+    let _query = info_span!("query", table = "users").entered();
+    info!(rows = 1, "fetched");
+}
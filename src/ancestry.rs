@@ -0,0 +1,174 @@
+use crate::source::{node_children, node_label};
+use crate::{PrettyFragment, PrettyTree};
+
+impl<'a> PrettyTree<'a> {
+    /// The nodes from the root down to the first node matching
+    /// `predicate`, in pre-order, both included. Shared nodes are followed
+    /// and fragments are skipped, like in [`select`](Self::select).
+    pub fn path_to(&self, predicate: impl Fn(&PrettyTree) -> bool) -> Option<Vec<PrettyTree<'a>>> {
+        let mut path = Vec::default();
+        roots(self)
+            .iter()
+            .any(|root| find(root, &predicate, &mut path, &mut Vec::default()))
+            .then_some(path)
+    }
+    /// Only the nodes matching `predicate`, with their subtrees, and their
+    /// ancestors. The other children of an ancestor are summarized as
+    /// `… N more`. Empty if nothing matches.
+    pub fn ancestry(&self, predicate: impl Fn(&PrettyTree) -> bool) -> PrettyTree<'a> {
+        let mut roots = roots(self)
+            .iter()
+            .filter_map(|x| prune(x, &predicate, &mut Vec::default()))
+            .collect::<Vec<_>>();
+        match (self, roots.len()) {
+            (_, 0) => PrettyTree::Empty,
+            (PrettyTree::Fragment(_), _) => PrettyTree::Fragment(PrettyFragment { nodes: roots }),
+            _ => roots.remove(0),
+        }
+    }
+    /// A "who contains me" view: the labels of the nodes matching
+    /// `predicate` as roots, each with its parents as children, their
+    /// parents below them, and so on up to the root. Paths through equal
+    /// ancestors are merged, and an ancestor with other children is marked
+    /// with `(… N more)`.
+    pub fn inverted(&self, predicate: impl Fn(&PrettyTree) -> bool) -> PrettyTree<'static> {
+        let mut matches = Vec::default();
+        for root in roots(self) {
+            ancestors(&root, &predicate, &mut Vec::default(), &mut matches, &mut Vec::default());
+        }
+        let mut inverted = Vec::<Inverted>::default();
+        for (label, ancestors) in matches {
+            let mut level = &mut inverted;
+            for label in std::iter::once(label).chain(ancestors.into_iter().rev()) {
+                let ix = match level.iter().position(|x| x.label == label) {
+                    Some(ix) => ix,
+                    None => {
+                        level.push(Inverted { label, parents: Vec::default() });
+                        level.len() - 1
+                    }
+                };
+                level = &mut level[ix].parents;
+            }
+        }
+        let mut roots = inverted.into_iter().map(Inverted::into_tree).collect::<Vec<_>>();
+        match roots.len() {
+            0 => PrettyTree::Empty,
+            1 => roots.remove(0),
+            _ => PrettyTree::Fragment(PrettyFragment { nodes: roots }),
+        }
+    }
+}
+
+/// The trees of a top-level fragment, or the tree itself.
+fn roots<'a>(tree: &PrettyTree<'a>) -> Vec<PrettyTree<'a>> {
+    match tree {
+        PrettyTree::Empty => Vec::default(),
+        PrettyTree::Fragment(_) => node_children(tree),
+        tree => vec![tree.clone()],
+    }
+}
+
+/// Calls `f` with the node a shared node points to, or `None` if that
+/// node is already on the path through `shared`.
+fn resolve<'a, T>(
+    node: &PrettyTree<'a>,
+    shared: &mut Vec<usize>,
+    f: impl FnOnce(&PrettyTree<'a>, &mut Vec<usize>) -> T,
+) -> Option<T> {
+    let PrettyTree::Shared(x) = node else { return Some(f(node, shared)) };
+    if shared.contains(&x.id()) {
+        return None
+    }
+    shared.push(x.id());
+    let inner = x.lock().clone();
+    let result = f(&inner, shared);
+    shared.pop();
+    Some(result)
+}
+
+fn find<'a>(
+    node: &PrettyTree<'a>,
+    predicate: &dyn Fn(&PrettyTree) -> bool,
+    path: &mut Vec<PrettyTree<'a>>,
+    shared: &mut Vec<usize>,
+) -> bool {
+    resolve(node, shared, |node, shared| {
+        path.push(node.clone());
+        if predicate(node) || node_children(node).iter().any(|x| find(x, predicate, path, shared)) {
+            return true
+        }
+        path.pop();
+        false
+    })
+    .unwrap_or_default()
+}
+
+fn prune<'a>(
+    node: &PrettyTree<'a>,
+    predicate: &dyn Fn(&PrettyTree) -> bool,
+    shared: &mut Vec<usize>,
+) -> Option<PrettyTree<'a>> {
+    resolve(node, shared, |node, shared| {
+        if predicate(node) {
+            return Some(node.clone())
+        }
+        let children = node_children(node);
+        let mut kept = children.iter().filter_map(|x| prune(x, predicate, shared)).collect::<Vec<_>>();
+        if kept.is_empty() {
+            return None
+        }
+        if kept.len() < children.len() {
+            kept.push(PrettyTree::Value(format!("… {} more", children.len() - kept.len()).into()));
+        }
+        Some(PrettyTree::branch_of(label(node), kept))
+    })
+    .flatten()
+}
+
+/// Collects the label of each node matching `predicate` together with the
+/// labels of its ancestors, root first.
+fn ancestors(
+    node: &PrettyTree,
+    predicate: &dyn Fn(&PrettyTree) -> bool,
+    path: &mut Vec<String>,
+    matches: &mut Vec<(String, Vec<String>)>,
+    shared: &mut Vec<usize>,
+) {
+    resolve(node, shared, |node, shared| {
+        if predicate(node) {
+            matches.push((label(node), path.clone()));
+        }
+        let children = node_children(node);
+        let label = match children.len() {
+            0 | 1 => label(node),
+            count => format!("{} (… {} more)", label(node), count - 1),
+        };
+        path.push(label);
+        for child in children.iter() {
+            ancestors(child, predicate, path, matches, shared);
+        }
+        path.pop();
+    });
+}
+
+/// A node's label, with `[]` for lists without a type name.
+fn label(node: &PrettyTree) -> String {
+    match node_label(node) {
+        label if label.is_empty() => String::from("[]"),
+        label => label,
+    }
+}
+
+struct Inverted {
+    label: String,
+    parents: Vec<Inverted>,
+}
+
+impl Inverted {
+    fn into_tree(self) -> PrettyTree<'static> {
+        if self.parents.is_empty() {
+            return PrettyTree::Value(self.label.into())
+        }
+        PrettyTree::branch_of(self.label, self.parents.into_iter().map(Inverted::into_tree))
+    }
+}
@@ -0,0 +1,119 @@
+use std::path::Path;
+
+use clap::ValueEnum;
+use pretty_tree::PrettyTree;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum InputFormat {
+    Json,
+    Yaml,
+    Toml,
+    Xml,
+}
+
+impl InputFormat {
+    /// Guesses from the file extension, then from the first character, then
+    /// from TOML `key = value` or `[table]` lines. Text starting with `[`
+    /// that parses as JSON is JSON.
+    pub fn detect(path: Option<&Path>, text: &str) -> Self {
+        let extension = path.and_then(Path::extension).and_then(|x| x.to_str());
+        match extension {
+            Some("json") => return Self::Json,
+            Some("yaml" | "yml") => return Self::Yaml,
+            Some("toml") => return Self::Toml,
+            Some("xml" | "html" | "svg") => return Self::Xml,
+            _ => (),
+        }
+        match text.trim_start().chars().next() {
+            Some('<') => Self::Xml,
+            Some('{') => Self::Json,
+            Some('[') if serde_json::from_str::<serde_json::Value>(text).is_ok() || !is_toml(text) => Self::Json,
+            _ if is_toml(text) => Self::Toml,
+            _ => Self::Yaml,
+        }
+    }
+}
+
+/// Whether the first line that is not blank or a comment is a TOML
+/// `[table]` header or `key = value` pair. Headers only count with bare
+/// keys starting with a letter, so that JSON arrays such as `["a"]` or
+/// `[1]` are not taken for one.
+fn is_toml(text: &str) -> bool {
+    let bare = |x: &str| !x.is_empty() && x.chars().all(|x| x.is_alphanumeric() || matches!(x, '_' | '-' | '.'));
+    let Some(line) = text.lines().map(str::trim).find(|x| !x.is_empty() && !x.starts_with('#')) else {
+        return false
+    };
+    if let Some(header) = line.strip_prefix('[') {
+        let header = header.split_once(']').map(|(x, _)| x.trim_start_matches('[').trim());
+        return header.is_some_and(|x| bare(x) && x.starts_with(|x: char| x.is_alphabetic() || x == '_'))
+    }
+    line.split_once('=').is_some_and(|(key, _)| bare(&key.trim().replace(['"', '\'', ' '], "")))
+}
+
+pub fn parse(format: InputFormat, text: &str) -> Result<PrettyTree<'static>, String> {
+    let value: serde_json::Value = match format {
+        InputFormat::Json => serde_json::from_str(text).map_err(|x| x.to_string())?,
+        InputFormat::Yaml => serde_yaml::from_str(text).map_err(|x| x.to_string())?,
+        InputFormat::Toml => toml::from_str(text).map_err(|x| x.to_string())?,
+        InputFormat::Xml => {
+            let document = roxmltree::Document::parse(text).map_err(|x| x.to_string())?;
+            return Ok(element(document.root_element()))
+        }
+    };
+    Ok(value_tree(&value).into_owned())
+}
+
+/// Scalars are leaves, and arrays and objects branches. An object's entries
+/// are `key: value` fields, or branches labeled with the key when the value
+/// is a non-empty array or object.
+///
+/// This is not the library's `ToPrettyTree` impl for `serde_json::Value`,
+/// which labels scalars with their variant, as in `Bool(true)`, and whose
+/// output is kept as it is for existing users.
+fn value_tree(value: &serde_json::Value) -> PrettyTree<'_> {
+    use serde_json::Value;
+    match value {
+        Value::Null => PrettyTree::leaf("null"),
+        Value::Bool(x) => PrettyTree::from(*x),
+        Value::Number(x) => PrettyTree::Value(x.to_string().into()),
+        Value::String(x) => PrettyTree::String(x.as_str().into()),
+        Value::Array(xs) => PrettyTree::branch_of("Array", xs.iter().map(value_tree)),
+        Value::Object(xs) => PrettyTree::branch_of("Object", xs.iter().map(|(key, value)| entry(key, value))),
+    }
+}
+
+fn entry<'a>(key: &'a str, value: &'a serde_json::Value) -> PrettyTree<'a> {
+    use serde_json::Value;
+    match value {
+        Value::Array(xs) if xs.is_empty() => PrettyTree::key_value(key, PrettyTree::leaf("[]")),
+        Value::Object(xs) if xs.is_empty() => PrettyTree::key_value(key, PrettyTree::leaf("{}")),
+        Value::Array(xs) => PrettyTree::branch_of(key, xs.iter().map(value_tree)),
+        Value::Object(xs) => PrettyTree::branch_of(key, xs.iter().map(|(key, value)| entry(key, value))),
+        value => PrettyTree::key_value(key, value_tree(value)),
+    }
+}
+
+/// An element is a branch labeled with its tag name, holding `@name: value`
+/// attributes, then child elements and text. An element holding only text
+/// becomes a `name: "text"` leaf.
+fn element(node: roxmltree::Node) -> PrettyTree<'static> {
+    let name = node.tag_name().name().to_string();
+    let attributes = node
+        .attributes()
+        .map(|x| PrettyTree::key_value(format!("@{}", x.name()), PrettyTree::String(x.value().to_string().into())));
+    let children = node.children().filter_map(|x| match x.node_type() {
+        roxmltree::NodeType::Element => Some(element(x)),
+        roxmltree::NodeType::Text => {
+            let text = x.text()?.trim();
+            (!text.is_empty()).then(|| PrettyTree::String(text.to_string().into()))
+        }
+        _ => None,
+    });
+    let children = attributes.chain(children).collect::<Vec<_>>();
+    match children.as_slice() {
+        [PrettyTree::String(_)] if node.attributes().len() == 0 => {
+            PrettyTree::key_value(name, children.into_iter().next().unwrap())
+        }
+        _ => PrettyTree::branch_of(name, children),
+    }
+}
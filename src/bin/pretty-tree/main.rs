@@ -0,0 +1,197 @@
+use std::io::{IsTerminal, Read, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::{Parser, ValueEnum};
+use pretty_tree::fs::{dir_tree, DirTreeOptions};
+use pretty_tree::{
+    Formatter, FormatterStyle, GlyphStyle, Layout, PrettyBranch, PrettyFragment, PrettyList, PrettyMetric, PrettyTree,
+};
+
+mod input;
+
+use input::InputFormat;
+
+/// Pretty-prints JSON, YAML, TOML and XML as a tree, or lists a directory
+/// like tree(1).
+#[derive(Debug, Parser)]
+#[command(name = "pretty-tree", version)]
+struct Args {
+    /// The file to read or the directory to list; reads stdin if omitted or `-`.
+    file: Option<PathBuf>,
+    /// The input format. Detected from the file extension or content if omitted.
+    #[arg(short, long)]
+    format: Option<InputFormat>,
+    /// Only print the nodes matching a dot-separated path such as `users.*.name`.
+    #[arg(short, long)]
+    select: Option<String>,
+    /// Print at most this many levels.
+    #[arg(short, long)]
+    depth: Option<usize>,
+    /// Truncate lines to this many characters (text output only).
+    #[arg(short, long)]
+    width: Option<usize>,
+    /// The characters used to draw edges (text output only).
+    #[arg(short, long, value_enum, default_value_t = Glyphs::Rounded)]
+    glyphs: Glyphs,
+    /// How nodes are arranged (text output only).
+    #[arg(short, long, value_enum, default_value_t = Arrangement::Indented)]
+    layout: Arrangement,
+    /// When to color the output (text output only).
+    #[arg(long, value_enum, default_value_t = Color::Auto)]
+    color: Color,
+    #[arg(short, long, value_enum, default_value_t = Output::Text)]
+    output: Output,
+    /// List hidden entries (directories only).
+    #[arg(short, long)]
+    all: bool,
+    /// Skip entries matching a gitignore-style glob (directories only).
+    #[arg(short = 'x', long, value_name = "GLOB")]
+    exclude: Vec<String>,
+    /// Show file sizes (directories only).
+    #[arg(long)]
+    sizes: bool,
+    /// Show permissions (directories only).
+    #[arg(long)]
+    permissions: bool,
+    /// Sort directories together with files (directories only).
+    #[arg(long)]
+    no_dirs_first: bool,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Glyphs {
+    Rounded,
+    Square,
+    Ascii,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Arrangement {
+    Indented,
+    TopDown,
+    LeftToRight,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Color {
+    Auto,
+    Always,
+    Never,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Output {
+    Text,
+    Json,
+    Dot,
+    Html,
+}
+
+fn main() -> ExitCode {
+    match run(Args::parse()) {
+        Ok(output) => {
+            // A closed pipe, as with `| head`, is not an error.
+            let _ = writeln!(std::io::stdout(), "{}", output.trim_end());
+            ExitCode::SUCCESS
+        }
+        Err(error) => {
+            eprintln!("pretty-tree: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: Args) -> Result<String, String> {
+    let file = args.file.clone().filter(|x| x.as_os_str() != "-");
+    let mut tree = match file.as_ref() {
+        Some(path) if path.is_dir() => {
+            let options = DirTreeOptions::default()
+                .show_hidden(args.all)
+                .max_depth(args.depth.map(|x| x.saturating_sub(1)))
+                .sizes(args.sizes)
+                .permissions(args.permissions)
+                .dirs_first(!args.no_dirs_first);
+            let options = args.exclude.iter().fold(options, |options, x| options.exclude(x));
+            dir_tree(path, &options).map_err(|x| format!("{}: {x}", path.display()))?
+        }
+        Some(path) => {
+            let text = std::fs::read_to_string(path).map_err(|x| format!("{}: {x}", path.display()))?;
+            let format = args.format.unwrap_or_else(|| InputFormat::detect(Some(path), &text));
+            input::parse(format, &text)?
+        }
+        None => {
+            let mut text = String::default();
+            std::io::stdin().read_to_string(&mut text).map_err(|x| x.to_string())?;
+            let format = args.format.unwrap_or_else(|| InputFormat::detect(None, &text));
+            input::parse(format, &text)?
+        }
+    };
+    // Directory listings are already cut off at the depth limit, which
+    // counts the root as a level here but not in `DirTreeOptions`.
+    let depth = args.depth.filter(|_| !file.as_ref().is_some_and(|x| x.is_dir()));
+    if let Some(selector) = args.select.as_deref() {
+        tree = match tree.select(selector).as_slice() {
+            [] => return Err(format!("nothing matches `{selector}`")),
+            [node] => node.clone(),
+            nodes => PrettyTree::fragment(nodes.to_vec()),
+        };
+    }
+    if let Output::Text = args.output {
+        let use_color = match args.color {
+            Color::Auto => std::io::stdout().is_terminal(),
+            Color::Always => {
+                colored::control::set_override(true);
+                true
+            }
+            Color::Never => false,
+        };
+        let glyphs = match args.glyphs {
+            Glyphs::Rounded => GlyphStyle::Rounded,
+            Glyphs::Square => GlyphStyle::Square,
+            Glyphs::Ascii => GlyphStyle::Ascii,
+        };
+        let layout = match args.layout {
+            Arrangement::Indented => Layout::Indented,
+            Arrangement::TopDown => Layout::TopDown,
+            Arrangement::LeftToRight => Layout::LeftToRight,
+        };
+        let style = FormatterStyle::default()
+            .use_color(use_color)
+            .glyphs(glyphs)
+            .layout(layout)
+            .max_depth(depth)
+            .max_width(args.width);
+        return Ok(tree.format(&Formatter::new(style)))
+    }
+    if let Some(depth) = depth {
+        tree = cut(tree, depth);
+    }
+    Ok(match args.output {
+        Output::Json => serde_json::to_string_pretty(&tree.to_json()).map_err(|x| x.to_string())?,
+        Output::Dot => tree.to_dot(),
+        Output::Html => tree.to_html(),
+        Output::Text => unreachable!(),
+    })
+}
+
+/// The tree with at most `depth` levels, deeper nodes replaced by a `…`
+/// leaf. Unlike `PrettyTree::from_source`, nodes keep their kind, so fields
+/// are still exported as keys and values. Parsed input has no shared nodes,
+/// so they are kept as they are.
+fn cut(tree: PrettyTree<'static>, depth: usize) -> PrettyTree<'static> {
+    let cut_all = |nodes: Vec<PrettyTree<'static>>| match depth {
+        0 | 1 if nodes.iter().all(|x| matches!(x, PrettyTree::Empty)) => Vec::default(),
+        0 | 1 => vec![PrettyTree::value("…")],
+        _ => nodes.into_iter().map(|x| cut(x, depth - 1)).collect(),
+    };
+    match tree {
+        PrettyTree::Branch(x) => PrettyTree::Branch(PrettyBranch { children: cut_all(x.children), ..x }),
+        PrettyTree::List(x) => PrettyTree::List(PrettyList { nodes: cut_all(x.nodes), ..x }),
+        PrettyTree::Fragment(x) => {
+            PrettyTree::Fragment(PrettyFragment { nodes: x.nodes.into_iter().map(|x| cut(x, depth)).collect() })
+        }
+        PrettyTree::Metric(x) => PrettyTree::Metric(PrettyMetric { node: Box::new(cut(*x.node, depth)), ..x }),
+        tree => tree,
+    }
+}
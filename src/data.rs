@@ -1,59 +1,71 @@
-use std::{collections::{BTreeMap, BTreeSet, HashMap, HashSet}, fmt::Display};
+use std::{borrow::Cow, collections::{BTreeMap, BTreeSet, HashMap, HashSet}, fmt::Display};
 
-use crate::ToPrettyTree;
+use crate::{PrettyMetric, PrettyShared, ToPrettyTree};
 
-#[derive(Debug, Clone)]
-pub enum PrettyTree {
+/// A renderable tree.
+///
+/// Text is stored as `Cow<'a, str>`, so labels and leaves can borrow from
+/// the data being printed instead of allocating. Use
+/// [`PrettyTree::into_owned`] to detach a tree from its source.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub enum PrettyTree<'a> {
+    #[default]
     Empty,
     /// A terminal leaf node.
-    Value(String),
-    /// A terminal leaf node.
-    String(String),
+    Value(Cow<'a, str>),
+    /// A terminal leaf node, printed quoted and escaped.
+    String(Cow<'a, str>),
     /// A branch node.
-    Branch(PrettyBranch),
+    Branch(PrettyBranch<'a>),
     /// A fragment node.
-    Fragment(PrettyFragment),
-    // /// A list node.
-    // List(PrettyList),
+    Fragment(PrettyFragment<'a>),
+    /// A node that may be referenced from several parents.
+    Shared(PrettyShared),
+    /// A list node, optionally labeled with the type of the collection.
+    List(PrettyList<'a>),
+    /// A `key: value` leaf, as built by [`PrettyTree::key_value`].
+    Field(PrettyField<'a>),
+    /// A node with a metric, as built by [`PrettyTree::with_metric`].
+    Metric(PrettyMetric<'a>),
 }
 
-impl PrettyTree {
+impl<'a> PrettyTree<'a> {
     pub fn empty() -> Self { Self::Empty }
     pub fn value(value: impl ToString) -> Self {
         let value = value.to_string();
-        Self::Value(format!("{value}"))
+        Self::Value(value.into())
     }
     pub fn string(value: impl ToString) -> Self {
         let value = value.to_string();
-        Self::Value(format!("{value:?}"))
+        Self::Value(format!("{value:?}").into())
     }
     pub fn str(value: impl AsRef<str>) -> Self {
         let value = value.as_ref();
-        Self::Value(format!("{value:?}"))
+        Self::Value(format!("{value:?}").into())
     }
-    pub fn leaf(value: impl AsRef<str>) -> Self {
-        Self::Value(value.as_ref().to_owned())
+    pub fn leaf(value: impl Into<Cow<'a, str>>) -> Self {
+        Self::Value(value.into())
     }
-    pub fn fragment<T: ToPrettyTree>(list: impl IntoIterator<Item = T>) -> Self {
-        Self::Fragment(PrettyFragment { nodes: list.into_iter().map(|x| x.to_pretty_tree()).collect() })
+    pub fn fragment<T: Into<PrettyTree<'a>>>(list: impl IntoIterator<Item = T>) -> Self {
+        Self::Fragment(PrettyFragment { nodes: list.into_iter().map(Into::into).collect() })
     }
-    pub fn branch_of<Type: ToPrettyTree>(
-        label: impl AsRef<str>,
+    pub fn branch_of<Type: Into<PrettyTree<'a>>>(
+        label: impl Into<Cow<'a, str>>,
         children: impl IntoIterator<Item = Type>
     ) -> Self {
         Self::Branch(PrettyBranch {
-            label: label.as_ref().to_string(),
-            children: children.into_iter().map(|x| x.to_pretty_tree()).collect(),
+            label: label.into(),
+            children: children.into_iter().map(Into::into).collect(),
         })
     }
     pub fn key_value(
-        key: impl AsRef<str>,
-        value: impl ToPrettyTree
+        key: impl Into<Cow<'a, str>>,
+        value: impl Into<PrettyTree<'a>>
     ) -> Self {
-        let key = key.as_ref().to_string();
-        match value.to_pretty_tree() {
-            PrettyTree::String(text) => PrettyTree::Value(format!("{key}: {:?}", text)),
-            PrettyTree::Value(text) => PrettyTree::Value(format!("{key}: {}", text)),
+        let key = key.into();
+        match value.into() {
+            PrettyTree::String(text) => PrettyTree::Field(PrettyField::quoted(key, text)),
+            PrettyTree::Value(value) => PrettyTree::Field(PrettyField::new(key, value)),
             tree => {
                 Self::Branch(PrettyBranch {
                     label: key,
@@ -64,24 +76,111 @@ impl PrettyTree {
 
     }
     pub fn some_value(value: impl Into<PrettyValue>) -> Self {
-        Self::Value(value.into().0)
+        Self::Value(value.into().0.into())
     }
-    pub fn some_branch(branch: impl Into<PrettyBranch>) -> Self {
+    pub fn some_branch(branch: impl Into<PrettyBranch<'a>>) -> Self {
         Self::Branch(branch.into())
     }
-    pub fn some_fragment(fragment: impl Into<PrettyFragment>) -> Self {
+    pub fn some_fragment(fragment: impl Into<PrettyFragment<'a>>) -> Self {
         Self::Fragment(fragment.into())
     }
+    /// A list labeled with the raw `std::any::type_name` of `Type`, which the
+    /// formatter shortens or hides according to its `TypeHeaders` setting.
+    pub fn list_of<Type: ?Sized, T: Into<PrettyTree<'a>>>(items: impl IntoIterator<Item = T>) -> Self {
+        Self::List(PrettyList::from_iter(Some(std::any::type_name::<Type>()), items))
+    }
+    /// Like [`PrettyTree::list_of`], for collections without a stable
+    /// iteration order.
+    pub fn unordered_list_of<Type: ?Sized, T: Into<PrettyTree<'a>>>(items: impl IntoIterator<Item = T>) -> Self {
+        let list = PrettyList::from_iter(Some(std::any::type_name::<Type>()), items);
+        Self::List(list.unordered(true))
+    }
+    /// The text unordered lists are sorted by: a leaf's printed text, a
+    /// branch's label followed by its children's keys in parentheses, or
+    /// the keys of a list's nodes joined by `, `. Runs of digits in the
+    /// keys are compared as numbers, so `2` sorts before `10`. A shared
+    /// node inside itself has the key `↑ ref`.
+    pub fn sort_key(&self) -> String {
+        self.key(&mut Vec::default())
+    }
+    /// Like [`sort_key`](Self::sort_key), with the ids of the shared nodes
+    /// on the current path in `shared`.
+    fn key(&self, shared: &mut Vec<usize>) -> String {
+        let mut keys = |nodes: &[PrettyTree]| nodes.iter().map(|x| x.key(shared)).collect::<Vec<_>>().join(", ");
+        match self {
+            Self::Empty => String::default(),
+            Self::Value(x) => x.to_string(),
+            Self::String(x) => format!("{x:?}"),
+            Self::Branch(x) if x.children.is_empty() => x.label.to_string(),
+            Self::Branch(x) => format!("{}({})", x.label, keys(&x.children)),
+            Self::Fragment(x) => keys(&x.nodes),
+            Self::Shared(x) if shared.contains(&x.id()) => String::from("↑ ref"),
+            Self::Shared(x) => {
+                shared.push(x.id());
+                let key = x.lock().key(shared);
+                shared.pop();
+                key
+            }
+            Self::List(x) => {
+                let nodes = x.sorted(shared);
+                nodes.iter().map(|x| x.key(shared)).collect::<Vec<_>>().join(", ")
+            }
+            Self::Field(x) => x.to_string(),
+            Self::Metric(x) => x.node.key(shared),
+        }
+    }
+    /// Copies all borrowed text, detaching the tree from its source.
+    pub fn into_owned(self) -> PrettyTree<'static> {
+        match self {
+            Self::Empty => PrettyTree::Empty,
+            Self::Value(x) => PrettyTree::Value(Cow::Owned(x.into_owned())),
+            Self::String(x) => PrettyTree::String(Cow::Owned(x.into_owned())),
+            Self::Branch(x) => PrettyTree::Branch(x.into_owned()),
+            Self::Fragment(x) => PrettyTree::Fragment(x.into_owned()),
+            Self::Shared(x) => PrettyTree::Shared(x),
+            Self::List(x) => PrettyTree::List(x.into_owned()),
+            Self::Field(x) => PrettyTree::Field(x.into_owned()),
+            Self::Metric(x) => PrettyTree::Metric(x.into_owned()),
+        }
+    }
+}
+
+/// Compares text like `str::cmp`, except that runs of digits are compared
+/// as numbers. Text that only differs in leading zeros, like `7` and `07`,
+/// falls back to `str::cmp`.
+fn natural_cmp(a: &str, b: &str) -> std::cmp::Ordering {
+    natural(a, b).then_with(|| a.cmp(b))
 }
 
-impl Default for PrettyTree {
-    fn default() -> Self { PrettyTree::Empty }
+fn natural(a: &str, b: &str) -> std::cmp::Ordering {
+    let (mut a, mut b) = (a, b);
+    loop {
+        let (Some(x), Some(y)) = (a.chars().next(), b.chars().next()) else {
+            return a.len().cmp(&b.len())
+        };
+        if x.is_ascii_digit() && y.is_ascii_digit() {
+            let a_end = a.find(|c: char| !c.is_ascii_digit()).unwrap_or(a.len());
+            let b_end = b.find(|c: char| !c.is_ascii_digit()).unwrap_or(b.len());
+            let (a_digits, b_digits) = (a[..a_end].trim_start_matches('0'), b[..b_end].trim_start_matches('0'));
+            let ordering = a_digits.len().cmp(&b_digits.len()).then_with(|| a_digits.cmp(b_digits));
+            if ordering.is_ne() {
+                return ordering
+            }
+            (a, b) = (&a[a_end..], &b[b_end..]);
+            continue
+        }
+        if x != y {
+            return x.cmp(&y)
+        }
+        (a, b) = (&a[x.len_utf8()..], &b[y.len_utf8()..]);
+    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct PrettyValue(String);
 
 impl PrettyValue {
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(value: impl AsRef<str>) -> Self {
         Self(value.as_ref().to_string())
     }
@@ -90,81 +189,168 @@ impl PrettyValue {
     }
 }
 
-#[derive(Debug, Clone)]
-pub struct PrettyBranch {
-    pub label: String,
-    pub children: Vec<PrettyTree>,
+/// A labeled node with children.
+///
+/// The `with_*` builder methods consume and return the branch, so chaining
+/// them does not copy the children built so far.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PrettyBranch<'a> {
+    pub label: Cow<'a, str>,
+    pub children: Vec<PrettyTree<'a>>,
 }
 
-impl PrettyBranch {
-    pub fn new(label: impl AsRef<str>) -> Self {
-        let label = label.as_ref().to_string();
-        Self { label, children: Vec::default() }
+impl<'a> PrettyBranch<'a> {
+    pub fn new(label: impl Into<Cow<'a, str>>) -> Self {
+        Self { label: label.into(), children: Vec::default() }
     }
-    pub fn with_child(&self, child: impl ToPrettyTree) -> Self {
-        let mut copy = self.clone();
-        copy.children.push(child.to_pretty_tree());
-        copy
+    pub fn with_child(mut self, child: impl Into<PrettyTree<'a>>) -> Self {
+        self.children.push(child.into());
+        self
     }
-    pub fn with_child_ref<T: ToPrettyTree>(&self, child: &T) -> Self {
-        let mut copy = self.clone();
-        copy.children.push(child.to_pretty_tree());
-        copy
+    pub fn with_child_ref<T: ToPrettyTree + ?Sized>(mut self, child: &'a T) -> Self {
+        self.children.push(child.to_pretty_tree());
+        self
     }
 
-    pub fn with_field(&self, key: impl AsRef<str>, child: impl ToPrettyTree) -> Self {
-        let mut copy = self.clone();
-        copy.children.push(PrettyTree::key_value(key, child.to_pretty_tree()));
-        copy
+    pub fn with_field(mut self, key: impl Into<Cow<'a, str>>, child: impl Into<PrettyTree<'a>>) -> Self {
+        self.children.push(PrettyTree::key_value(key, child));
+        self
     }
-    pub fn with_field_ref<T: ToPrettyTree>(&self, key: impl AsRef<str>, child: &T) -> Self {
-        let mut copy = self.clone();
-        copy.children.push(PrettyTree::key_value(key, child.to_pretty_tree()));
-        copy
+    pub fn with_field_ref<T: ToPrettyTree + ?Sized>(mut self, key: impl Into<Cow<'a, str>>, child: &'a T) -> Self {
+        self.children.push(PrettyTree::key_value(key, child.to_pretty_tree()));
+        self
     }
 
-    pub fn with_children<T: ToPrettyTree>(&self, children: impl IntoIterator<Item=T>) -> Self {
-        let mut copy = self.clone();
+    pub fn with_children<T: Into<PrettyTree<'a>>>(mut self, children: impl IntoIterator<Item=T>) -> Self {
+        self.children.extend(children.into_iter().map(Into::into));
+        self
+    }
+    pub fn with_children_slice<T: ToPrettyTree>(mut self, children: &'a [T]) -> Self {
+        let children = children.iter().map(|x| x.to_pretty_tree());
+        self.children.extend(children);
+        self
+    }
+    pub fn with_children_iter<T: ToPrettyTree + 'a>(mut self, children: impl IntoIterator<Item=&'a T>) -> Self {
         let children = children.into_iter().map(|x| x.to_pretty_tree());
-        copy.children.extend(children);
-        copy
-    }
-    pub fn with_children_slice<T: ToPrettyTree>(&self, children: impl AsRef<[T]>) -> Self {
-        let mut copy = self.clone();
-        let children = children.as_ref().into_iter().map(|x| x.to_pretty_tree());
-        copy.children.extend(children);
-        copy
-    }
-    pub fn with_children_iter<'a, T: ToPrettyTree + Clone + 'a>(&self, children: impl IntoIterator<Item=&'a T>) -> Self {
-        let mut copy = self.clone();
-        let children = children.into_iter().cloned().map(|x| x.to_pretty_tree());
-        copy.children.extend(children);
-        copy
-    }
-    pub fn from_iter<Child: ToPrettyTree>(
-        label: impl ToString,
+        self.children.extend(children);
+        self
+    }
+    pub fn from_iter<Child: Into<PrettyTree<'a>>>(
+        label: impl Into<Cow<'a, str>>,
         children: impl IntoIterator<Item = Child>
     ) -> Self {
         PrettyBranch {
-            label: label.to_string(),
-            children: children.into_iter().map(|x| x.to_pretty_tree()).collect(),
+            label: label.into(),
+            children: children.into_iter().map(Into::into).collect(),
+        }
+    }
+    pub fn into_owned(self) -> PrettyBranch<'static> {
+        PrettyBranch {
+            label: Cow::Owned(self.label.into_owned()),
+            children: self.children.into_iter().map(PrettyTree::into_owned).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PrettyFragment<'a> {
+    pub nodes: Vec<PrettyTree<'a>>
+}
+
+impl<'a> PrettyFragment<'a> {
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_iter<Value: Into<PrettyTree<'a>>>(list: impl IntoIterator<Item = Value>) -> Self {
+        Self { nodes: list.into_iter().map(Into::into).collect() }
+    }
+    pub fn into_owned(self) -> PrettyFragment<'static> {
+        PrettyFragment { nodes: self.nodes.into_iter().map(PrettyTree::into_owned).collect() }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PrettyList<'a> {
+    pub data_type: Option<Cow<'a, str>>,
+    pub nodes: Vec<PrettyTree<'a>>,
+    /// The nodes come from a collection without a stable iteration order,
+    /// such as a `HashMap`, and may be sorted before printing.
+    pub unordered: bool,
+}
+
+impl<'a> PrettyList<'a> {
+    pub fn from_iter<Value: Into<PrettyTree<'a>>>(
+        data_type: Option<impl Into<Cow<'a, str>>>,
+        list: impl IntoIterator<Item = Value>
+    ) -> Self {
+        Self {
+            data_type: data_type.map(Into::into),
+            nodes: list.into_iter().map(Into::into).collect(),
+            unordered: false,
+        }
+    }
+    pub fn unordered(self, unordered: bool) -> Self {
+        Self { unordered, ..self }
+    }
+    /// The nodes, sorted by [`PrettyTree::sort_key`] if the list is unordered.
+    pub fn sorted_nodes(&self) -> Cow<'_, [PrettyTree<'a>]> {
+        self.sorted(&mut Vec::default())
+    }
+    fn sorted(&self, shared: &mut Vec<usize>) -> Cow<'_, [PrettyTree<'a>]> {
+        if !self.unordered {
+            return Cow::Borrowed(&self.nodes)
+        }
+        let mut nodes = self.nodes
+            .iter()
+            .map(|x| (x.key(shared), x.clone()))
+            .collect::<Vec<_>>();
+        nodes.sort_by(|a, b| natural_cmp(&a.0, &b.0));
+        Cow::Owned(nodes.into_iter().map(|(_, x)| x).collect())
+    }
+    pub fn into_owned(self) -> PrettyList<'static> {
+        PrettyList {
+            data_type: self.data_type.map(|x| Cow::Owned(x.into_owned())),
+            nodes: self.nodes.into_iter().map(PrettyTree::into_owned).collect(),
+            unordered: self.unordered,
         }
     }
 }
 
-#[derive(Debug, Clone)]
-pub struct PrettyFragment {
-    pub nodes: Vec<PrettyTree>
+/// A leaf with a separate key and value, printed as `key: value`. Sibling
+/// fields can be aligned by the formatter.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PrettyField<'a> {
+    pub key: Cow<'a, str>,
+    /// The value without quotes, see [`printed_value`](Self::printed_value).
+    pub value: Cow<'a, str>,
+    /// The value is text, printed quoted and escaped like a
+    /// [`PrettyTree::String`].
+    pub quoted: bool,
 }
 
-impl PrettyFragment {
-    pub fn from_iter<Value: ToPrettyTree>(list: impl IntoIterator<Item = Value>) -> Self {
-        Self { nodes: list.into_iter().map(|x| x.to_pretty_tree()).collect() }
+impl<'a> PrettyField<'a> {
+    pub fn new(key: impl Into<Cow<'a, str>>, value: impl Into<Cow<'a, str>>) -> Self {
+        Self { key: key.into(), value: value.into(), quoted: false }
+    }
+    pub fn quoted(key: impl Into<Cow<'a, str>>, value: impl Into<Cow<'a, str>>) -> Self {
+        Self { quoted: true, ..Self::new(key, value) }
+    }
+    /// The value as printed, e.g. quoted for text.
+    pub fn printed_value(&self) -> Cow<'_, str> {
+        match self.quoted {
+            true => Cow::Owned(format!("{:?}", self.value)),
+            false => Cow::Borrowed(&self.value),
+        }
+    }
+    pub fn into_owned(self) -> PrettyField<'static> {
+        PrettyField {
+            key: Cow::Owned(self.key.into_owned()),
+            value: Cow::Owned(self.value.into_owned()),
+            quoted: self.quoted,
+        }
     }
 }
 
-// #[derive(Debug, Clone)]
-// pub struct PrettyList {
-//     pub data_type: Option<String>,
-//     pub nodes: Vec<PrettyTree>,
-// }
+impl Display for PrettyField<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.key, self.printed_value())
+    }
+}
@@ -0,0 +1,190 @@
+use std::fmt::{Debug, Display};
+
+use crate::{PrettyTree, ToPrettyTree};
+
+/// Builds a tree from the `{:#?}` output of `value`.
+///
+/// Structs, tuple structs, tuples, lists, maps and sets become branches;
+/// everything else is a leaf. If the output cannot be parsed, for example
+/// because of a hand-written `Debug` impl, the single-line `{:?}` output
+/// is returned as one leaf.
+pub fn from_debug<T: Debug + ?Sized>(value: &T) -> PrettyTree<'static> {
+    let text = format!("{value:#?}");
+    let mut parser = Parser { text: &text, pos: 0 };
+    match parser.value() {
+        Some(node) if parser.at_end() => node.into_tree(),
+        _ => PrettyTree::Value(format!("{value:?}").into()),
+    }
+}
+
+/// Renders the wrapped value as a single leaf via `Display`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DisplayLeaf<T>(pub T);
+
+/// Renders the wrapped value as a single leaf via `Debug`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DebugLeaf<T>(pub T);
+
+impl<T: Display> ToPrettyTree for DisplayLeaf<T> {
+    fn to_pretty_tree(&self) -> PrettyTree<'_> {
+        PrettyTree::Value(self.0.to_string().into())
+    }
+}
+impl<T: Debug> ToPrettyTree for DebugLeaf<T> {
+    fn to_pretty_tree(&self) -> PrettyTree<'_> {
+        PrettyTree::Value(format!("{:?}", self.0).into())
+    }
+}
+impl<T: Display> From<DisplayLeaf<T>> for PrettyTree<'_> {
+    fn from(value: DisplayLeaf<T>) -> Self { value.to_pretty_tree().into_owned() }
+}
+impl<T: Debug> From<DebugLeaf<T>> for PrettyTree<'_> {
+    fn from(value: DebugLeaf<T>) -> Self { value.to_pretty_tree().into_owned() }
+}
+
+enum Node {
+    /// A literal, identifier or unit struct, kept verbatim.
+    Atom(String),
+    /// `Name { .. }`, `Name(..)`, `(..)`, `[..]` or `{..}`.
+    Group { name: Option<String>, open: char, items: Vec<(Option<String>, Node)> },
+}
+
+impl Node {
+    fn into_tree(self) -> PrettyTree<'static> {
+        match self {
+            Node::Atom(x) => PrettyTree::Value(x.into()),
+            Node::Group { name: Some(name), items, .. } if items.is_empty() => PrettyTree::Value(name.into()),
+            Node::Group { name: None, open, items } if items.is_empty() => PrettyTree::Value(delimiters(open).into()),
+            Node::Group { name, open, items } => {
+                let label = name.unwrap_or_else(|| delimiters(open).to_string());
+                PrettyTree::branch_of(label, items.into_iter().map(item))
+            }
+        }
+    }
+}
+
+/// A field, map entry or element. Unnamed collections under a key are
+/// spliced into the key's branch instead of adding a `[]` level.
+fn item((key, value): (Option<String>, Node)) -> PrettyTree<'static> {
+    match (key, value) {
+        (None, value) => value.into_tree(),
+        (Some(key), Node::Group { name: None, open, items }) if items.is_empty() => {
+            PrettyTree::key_value(key, PrettyTree::leaf(delimiters(open)))
+        }
+        (Some(key), Node::Group { name: None, items, .. }) => {
+            PrettyTree::branch_of(key, items.into_iter().map(item))
+        }
+        (Some(key), value) => PrettyTree::key_value(key, value.into_tree()),
+    }
+}
+
+fn delimiters(open: char) -> &'static str {
+    match open {
+        '[' => "[]",
+        '{' => "{}",
+        _ => "()",
+    }
+}
+
+/// A recursive descent parser for `{:#?}` output.
+struct Parser<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<char> {
+        self.text[self.pos..].chars().next()
+    }
+    fn bump(&mut self) -> Option<char> {
+        let char = self.peek()?;
+        self.pos += char.len_utf8();
+        Some(char)
+    }
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.bump();
+        }
+    }
+    fn at_end(&mut self) -> bool {
+        self.skip_whitespace();
+        self.pos == self.text.len()
+    }
+    fn value(&mut self) -> Option<Node> {
+        self.skip_whitespace();
+        match self.peek()? {
+            quote @ ('"' | '\'') => self.quoted(quote).map(Node::Atom),
+            open @ ('[' | '{' | '(') => self.group(None, open),
+            _ => {
+                let atom = self.atom()?;
+                match self.peek() {
+                    Some(open @ ('{' | '(')) => self.group(Some(atom), open),
+                    _ => Some(Node::Atom(atom)),
+                }
+            }
+        }
+    }
+    fn quoted(&mut self, quote: char) -> Option<String> {
+        let start = self.pos;
+        self.bump();
+        loop {
+            match self.bump()? {
+                '\\' => { self.bump()?; }
+                char if char == quote => break,
+                _ => (),
+            }
+        }
+        Some(self.text[start..self.pos].to_string())
+    }
+    /// Reads up to the next delimiter, separator or line break. `::` path
+    /// separators are part of the atom.
+    fn atom(&mut self) -> Option<String> {
+        let start = self.pos;
+        while let Some(char) = self.peek() {
+            if char == ':' && self.text[self.pos..].starts_with("::") {
+                self.pos += 2;
+                continue
+            }
+            if matches!(char, ',' | ':' | '(' | ')' | '[' | ']' | '{' | '}' | '\n') {
+                break
+            }
+            self.bump();
+        }
+        let atom = self.text[start..self.pos].trim();
+        (!atom.is_empty()).then(|| atom.to_string())
+    }
+    fn group(&mut self, name: Option<String>, open: char) -> Option<Node> {
+        let close = match open {
+            '[' => ']',
+            '{' => '}',
+            _ => ')',
+        };
+        self.bump();
+        let mut items = Vec::default();
+        loop {
+            self.skip_whitespace();
+            if self.peek()? == close {
+                self.bump();
+                break
+            }
+            let start = self.pos;
+            let value = self.value()?;
+            let end = self.pos;
+            self.skip_whitespace();
+            if self.peek() == Some(':') {
+                self.bump();
+                let key = self.text[start..end].split_whitespace().collect::<Vec<_>>().join(" ");
+                items.push((Some(key), self.value()?));
+            } else {
+                items.push((None, value));
+            }
+            self.skip_whitespace();
+            match self.peek()? {
+                ',' => { self.bump(); }
+                char if char == close => (),
+                _ => return None,
+            }
+        }
+        Some(Node::Group { name, open, items })
+    }
+}
@@ -0,0 +1,127 @@
+use crate::source::{node_children, node_label};
+use crate::PrettyTree;
+
+/// A tree reduced to labels and children, the shape all exports share.
+/// Fragments are flattened, and a shared node that contains itself is cut
+/// off with a `↑ ref` leaf.
+pub(crate) struct Outline {
+    pub label: String,
+    /// The key and unquoted value of a field, whose label is `key: value`.
+    pub field: Option<(String, String)>,
+    pub children: Vec<Outline>,
+}
+
+impl Outline {
+    pub fn roots(tree: &PrettyTree) -> Vec<Outline> {
+        match tree {
+            PrettyTree::Empty => Vec::default(),
+            PrettyTree::Fragment(_) => {
+                let mut path = Vec::default();
+                node_children(tree).iter().map(|x| Self::new(x, &mut path)).collect()
+            }
+            tree => vec![Self::new(tree, &mut Vec::default())],
+        }
+    }
+    fn new(tree: &PrettyTree, path: &mut Vec<usize>) -> Self {
+        if let PrettyTree::Shared(node) = tree {
+            if path.contains(&node.id()) {
+                return Self { label: String::from("↑ ref"), field: None, children: Vec::default() }
+            }
+            path.push(node.id());
+            let outline = Self::new(&node.lock(), path);
+            path.pop();
+            return outline
+        }
+        let children = node_children(tree).iter().map(|x| Self::new(x, path)).collect();
+        let field = match tree {
+            PrettyTree::Field(x) => Some((x.key.to_string(), x.value.to_string())),
+            _ => None,
+        };
+        Self { label: node_label(tree), field, children }
+    }
+}
+
+impl PrettyTree<'_> {
+    /// A Graphviz `digraph` with one box per node.
+    pub fn to_dot(&self) -> String {
+        fn visit(node: &Outline, next_id: &mut usize, out: &mut String) -> usize {
+            let id = *next_id;
+            *next_id += 1;
+            let label = node.label.replace('\\', "\\\\").replace('"', "\\\"");
+            out.push_str(&format!("    n{id} [label=\"{label}\"];\n"));
+            for child in node.children.iter() {
+                let child = visit(child, next_id, out);
+                out.push_str(&format!("    n{id} -> n{child};\n"));
+            }
+            id
+        }
+        let mut out = String::from("digraph tree {\n    node [shape=box, fontname=\"monospace\"];\n");
+        let mut next_id = 0;
+        for root in Outline::roots(self) {
+            visit(&root, &mut next_id, &mut out);
+        }
+        out.push_str("}\n");
+        out
+    }
+    /// Nested `<ul>` lists, the outermost one with the class `pretty-tree`.
+    /// Field keys and values are wrapped in `key` and `value` spans.
+    pub fn to_html(&self) -> String {
+        fn escape(text: &str) -> String {
+            text.replace('&', "&amp;")
+                .replace('<', "&lt;")
+                .replace('>', "&gt;")
+                .replace('"', "&quot;")
+        }
+        fn visit(node: &Outline, depth: usize, out: &mut String) {
+            let indent = "  ".repeat(depth);
+            let label = match node.field.as_ref() {
+                Some((key, value)) => {
+                    let (key, value) = (escape(key), escape(value));
+                    format!("<span class=\"key\">{key}</span>: <span class=\"value\">{value}</span>")
+                }
+                None => escape(&node.label),
+            };
+            if node.children.is_empty() {
+                out.push_str(&format!("{indent}<li>{label}</li>\n"));
+                return
+            }
+            out.push_str(&format!("{indent}<li>{label}\n{indent}  <ul>\n"));
+            for child in node.children.iter() {
+                visit(child, depth + 2, out);
+            }
+            out.push_str(&format!("{indent}  </ul>\n{indent}</li>\n"));
+        }
+        let mut out = String::from("<ul class=\"pretty-tree\">\n");
+        for root in Outline::roots(self) {
+            visit(&root, 1, &mut out);
+        }
+        out.push_str("</ul>\n");
+        out
+    }
+    /// `{"label": .., "children": [..]}` objects, with `children` omitted
+    /// for leaves, and `{"key": .., "value": ..}` for fields. A fragment
+    /// becomes an array of its nodes.
+    #[cfg(feature = "serde_json")]
+    pub fn to_json(&self) -> serde_json::Value {
+        fn visit(node: &Outline) -> serde_json::Value {
+            let mut object = serde_json::Map::default();
+            if let Some((key, value)) = node.field.as_ref() {
+                object.insert(String::from("key"), key.clone().into());
+                object.insert(String::from("value"), value.clone().into());
+                return object.into()
+            }
+            object.insert(String::from("label"), node.label.clone().into());
+            if !node.children.is_empty() {
+                let children = node.children.iter().map(visit).collect::<Vec<_>>();
+                object.insert(String::from("children"), children.into());
+            }
+            object.into()
+        }
+        let mut roots = Outline::roots(self).iter().map(visit).collect::<Vec<_>>();
+        match (self, roots.len()) {
+            (PrettyTree::Fragment(_), _) => roots.into(),
+            (_, 1) => roots.remove(0),
+            _ => serde_json::Value::Null,
+        }
+    }
+}
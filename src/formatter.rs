@@ -1,48 +1,364 @@
-use crate::{PrettyBranch, PrettyFragment, PrettyTree};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use crate::metric::metric_text;
+use crate::side_by_side::display_width;
+use crate::{short_type_name, PrettyBranch, PrettyField, PrettyFragment, PrettyList, PrettyShared, PrettyTree};
+use unicode_width::UnicodeWidthStr;
 use colored::Colorize;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct Formatter {
     columns: Vec<TreeColumn>,
+    /// Child indices from the root to the node being printed.
+    path: Vec<usize>,
+    /// The metric of the node being printed.
+    metric: Option<f64>,
+    focus: Option<Focus>,
     style: FormatterStyle,
+    state: Arc<Mutex<RenderState>>,
 }
 
 impl Formatter {
     pub const COLUMN_LENGTH: usize = 4;
     pub fn new(style: FormatterStyle) -> Self {
-        Self { columns: Default::default(), style }
+        Self { style, ..Self::default() }
     }
     pub fn map_formatter_style(self, f: impl FnOnce(FormatterStyle) -> FormatterStyle) -> Self {
-        Self { columns: self.columns, style: f(self.style) }
+        Self { style: f(self.style), ..self }
+    }
+    pub fn style(&self) -> FormatterStyle {
+        self.style
+    }
+    /// Print only part of the tree around the nodes matching a predicate.
+    pub fn focus(self, focus: Option<Focus>) -> Self {
+        Self { focus, ..self }
+    }
+    /// Whether nodes can be rendered on their own, without a layout or
+    /// focus that needs the whole tree.
+    fn is_plain(&self) -> bool {
+        self.style.layout == Layout::Indented && self.focus.is_none()
     }
 }
-impl Default for Formatter {
-    fn default() -> Self {
-        Self {
-            columns: Default::default(),
-            style: FormatterStyle::default(),
+
+/// Bookkeeping shared by every node of a single render.
+#[derive(Debug, Default)]
+struct RenderState {
+    /// How often each shared node is referenced, keyed by node identity.
+    shared_counts: HashMap<usize, usize>,
+    /// The `#N` ids of repeated shared nodes that have been printed.
+    shared_ids: HashMap<usize, usize>,
+    /// One entry per printed node, in output order, when requested.
+    lines: Option<Vec<NodeLine>>,
+    /// Whether any node has a metric.
+    has_metrics: bool,
+}
+
+/// Where a node ended up in the output.
+#[derive(Debug, Clone)]
+pub(crate) struct NodeLine {
+    pub depth: usize,
+    /// The node's text without glyphs or colors.
+    pub text: String,
+    /// Number of output lines the node's text takes.
+    pub line_count: usize,
+    /// The node's child indices from the root, or `None` for a line that
+    /// is not a node, such as a forest separator.
+    pub path: Option<Vec<usize>>,
+    pub metric: Option<f64>,
+}
+
+impl RenderState {
+    fn count_shared(&mut self, tree: &PrettyTree) {
+        match tree {
+            PrettyTree::Branch(x) => x.children.iter().for_each(|x| self.count_shared(x)),
+            PrettyTree::Fragment(x) => x.nodes.iter().for_each(|x| self.count_shared(x)),
+            PrettyTree::List(x) => x.nodes.iter().for_each(|x| self.count_shared(x)),
+            PrettyTree::Metric(x) => {
+                self.has_metrics = true;
+                self.count_shared(&x.node);
+            }
+            PrettyTree::Shared(x) => {
+                let count = self.shared_counts.entry(x.id()).or_default();
+                *count += 1;
+                if *count == 1 {
+                    self.count_shared(&x.lock());
+                }
+            }
+            _ => (),
         }
     }
 }
 
 #[derive(Debug, Clone, Copy)]
 pub struct FormatterStyle {
-    use_color: bool,
+    pub(crate) use_color: bool,
     compact_mode: bool,
+    collapse_repeats: bool,
+    back_references: bool,
+    pub(crate) max_depth: Option<usize>,
+    pub(crate) max_children: Option<usize>,
+    type_headers: TypeHeaders,
+    sort_unordered: bool,
+    pub(crate) glyphs: GlyphStyle,
+    max_width: Option<usize>,
+    pub(crate) highlight_changes: bool,
+    tables: bool,
+    align_fields: bool,
+    inline_width: Option<usize>,
+    inline_max_nodes: usize,
+    fold_chains: Option<usize>,
+    chain_separator: &'static str,
+    pub(crate) forest_separator: Option<&'static str>,
+    pub(crate) layout: Layout,
+    gutter: Option<Gutter>,
+    metric_bars: Option<usize>,
+}
+
+impl Default for FormatterStyle {
+    fn default() -> Self {
+        Self {
+            use_color: false,
+            compact_mode: false,
+            collapse_repeats: false,
+            back_references: false,
+            max_depth: None,
+            max_children: None,
+            type_headers: TypeHeaders::default(),
+            sort_unordered: true,
+            glyphs: GlyphStyle::default(),
+            max_width: None,
+            highlight_changes: false,
+            tables: false,
+            align_fields: true,
+            inline_width: None,
+            inline_max_nodes: 8,
+            fold_chains: None,
+            chain_separator: " › ",
+            forest_separator: None,
+            layout: Layout::default(),
+            gutter: None,
+            metric_bars: None,
+        }
+    }
 }
 
 impl FormatterStyle {
     pub fn use_color(self, color: bool) -> Self {
-        Self { use_color: color, compact_mode: self.compact_mode }
+        Self { use_color: color, ..self }
     }
+    /// Hide the type headers of lists, print single-element lists as their
+    /// element, and fold chains of single-child branches onto one line as
+    /// with [`fold_chains`](Self::fold_chains), without a length limit
+    /// unless one is set.
     pub fn compact_mode(self, compact_mode: bool) -> Self {
-        Self { use_color: self.use_color, compact_mode }
+        Self { compact_mode, ..self }
+    }
+    /// Render runs of structurally equal siblings once, marked with `×N`.
+    pub fn collapse_repeats(self, collapse_repeats: bool) -> Self {
+        Self { collapse_repeats, ..self }
+    }
+    /// Replace a branch or list that repeats an earlier sibling with a
+    /// `(same as #N)` leaf, where `N` is the position of the first
+    /// occurrence among the printed siblings, counting from 0.
+    pub fn back_references(self, back_references: bool) -> Self {
+        Self { back_references, ..self }
+    }
+    /// Print at most this many levels; deeper children are replaced by `…`.
+    pub fn max_depth(self, max_depth: Option<usize>) -> Self {
+        Self { max_depth, ..self }
+    }
+    /// Print at most this many children per node, followed by `… N more`.
+    pub fn max_children(self, max_children: Option<usize>) -> Self {
+        Self { max_children, ..self }
+    }
+    pub fn type_headers(self, type_headers: TypeHeaders) -> Self {
+        Self { type_headers, ..self }
+    }
+    /// Sort the nodes of unordered lists, such as `HashMap` entries, so the
+    /// output does not depend on hash order. On by default.
+    pub fn sort_unordered(self, sort_unordered: bool) -> Self {
+        Self { sort_unordered, ..self }
+    }
+    pub fn glyphs(self, glyphs: GlyphStyle) -> Self {
+        Self { glyphs, ..self }
+    }
+    /// Truncate lines longer than this many characters with `…`.
+    pub fn max_width(self, max_width: Option<usize>) -> Self {
+        Self { max_width, ..self }
+    }
+    /// Mark rows that differ in [`side_by_side`](crate::side_by_side) output.
+    pub fn highlight_changes(self, highlight_changes: bool) -> Self {
+        Self { highlight_changes, ..self }
+    }
+    /// Print children that are records with the same `key: value` fields,
+    /// such as the elements of a `Vec` of structs, as an aligned table.
+    pub fn tables(self, tables: bool) -> Self {
+        Self { tables, ..self }
+    }
+    /// Pad the values of sibling `key: value` fields into one column. On
+    /// by default.
+    pub fn align_fields(self, align_fields: bool) -> Self {
+        Self { align_fields, ..self }
+    }
+    /// Print small subtrees on one line, as `label(child, child)` or
+    /// `label { key: value }`, when the line, glyphs included, fits in this
+    /// many characters. Off by default.
+    pub fn inline_width(self, inline_width: Option<usize>) -> Self {
+        Self { inline_width, ..self }
+    }
+    /// The most nodes a subtree printed on one line may have. Defaults to 8.
+    pub fn inline_max_nodes(self, inline_max_nodes: usize) -> Self {
+        Self { inline_max_nodes, ..self }
+    }
+    /// Join chains of single-child branches onto one line, such as
+    /// `Expr › Paren › Literal`, with at most this many nodes per line.
+    /// Off by default.
+    pub fn fold_chains(self, fold_chains: Option<usize>) -> Self {
+        Self { fold_chains, ..self }
+    }
+    /// The separator between the nodes of a folded chain. Defaults to ` › `.
+    pub fn chain_separator(self, chain_separator: &'static str) -> Self {
+        Self { chain_separator, ..self }
+    }
+    /// A line printed between the trees of a top-level fragment, such as
+    /// `""` for a blank line. By default the trees follow each other.
+    pub fn forest_separator(self, forest_separator: Option<&'static str>) -> Self {
+        Self { forest_separator, ..self }
+    }
+    pub fn layout(self, layout: Layout) -> Self {
+        Self { layout, ..self }
+    }
+    /// Print a line number, node index or node path left of each line of
+    /// the indented layout.
+    pub fn gutter(self, gutter: Option<Gutter>) -> Self {
+        Self { gutter, ..self }
+    }
+    /// Draw a bar after each metric, proportional to it, with this many
+    /// characters for the largest one.
+    pub fn metric_bars(self, metric_bars: Option<usize>) -> Self {
+        Self { metric_bars, ..self }
     }
 }
 
-impl Default for FormatterStyle {
-    fn default() -> Self {
-        Self { use_color: false, compact_mode: false }
+/// How nodes are arranged on the page.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Layout {
+    /// One node per line, children indented below their parent.
+    #[default]
+    Indented,
+    /// Each parent centered above its children, joined by `┌─┴─┐`
+    /// connectors.
+    TopDown,
+    /// Each parent to the left of its children, which are stacked
+    /// vertically.
+    LeftToRight,
+}
+
+/// A predicate selecting the nodes a [`Focus`] is about.
+pub type NodePredicate = Arc<dyn Fn(&PrettyTree) -> bool + Send + Sync>;
+
+/// Which part of the tree to print, given the nodes to look at.
+#[derive(Clone)]
+pub enum Focus {
+    /// The matching nodes and their ancestors, see [`PrettyTree::ancestry`].
+    Ancestry(NodePredicate),
+    /// What contains the matching nodes, see [`PrettyTree::inverted`].
+    Inverted(NodePredicate),
+}
+
+impl Focus {
+    pub fn ancestry(predicate: impl Fn(&PrettyTree) -> bool + Send + Sync + 'static) -> Self {
+        Self::Ancestry(Arc::new(predicate))
+    }
+    pub fn inverted(predicate: impl Fn(&PrettyTree) -> bool + Send + Sync + 'static) -> Self {
+        Self::Inverted(Arc::new(predicate))
+    }
+}
+
+impl std::fmt::Debug for Focus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Ancestry(_) => write!(f, "Ancestry(..)"),
+            Self::Inverted(_) => write!(f, "Inverted(..)"),
+        }
+    }
+}
+
+/// What to print in the gutter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Gutter {
+    /// The line number, starting at 1.
+    LineNumbers,
+    /// The node's position in pre-order, starting at 0.
+    NodeIndex,
+    /// The node's child indices from the root, such as `0.3.1`, as taken
+    /// by [`PrettyTree::get_by_path`]. A folded chain has the path of its
+    /// last node. A run of repeats has the path of its first node; table
+    /// headers and `… N more` leaves have none.
+    Path,
+}
+
+/// How the type labels of list nodes, such as those produced for std
+/// collections, are printed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TypeHeaders {
+    /// The raw `std::any::type_name`, including module paths.
+    Full,
+    /// The type name without module paths, e.g. `HashMap<String, u32>`.
+    #[default]
+    Short,
+    /// No header; the list's children are printed without a label.
+    None,
+}
+
+impl TypeHeaders {
+    fn header<'a>(&self, type_name: &'a str) -> Option<Cow<'a, str>> {
+        match self {
+            Self::Full => Some(Cow::Borrowed(type_name)),
+            Self::Short => Some(Cow::Owned(short_type_name(type_name))),
+            Self::None => None,
+        }
+    }
+}
+
+/// The characters used to draw the tree's edges.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GlyphStyle {
+    /// `├╼`, `╰╼` and `╭╼`.
+    #[default]
+    Rounded,
+    /// `├─`, `└─` and `┌─`.
+    Square,
+    /// `+-`, `` `- `` and `/-`, for terminals and files without box drawing
+    /// characters.
+    Ascii,
+}
+
+impl GlyphStyle {
+    fn column(&self, column: &TreeColumn) -> &'static str {
+        match (self, column) {
+            (_, TreeColumn::Empty) => " ",
+            (Self::Ascii, TreeColumn::VerticalBar) => "|",
+            (_, TreeColumn::VerticalBar) => "│",
+            (Self::Rounded, TreeColumn::UpThenRight) => "╭",
+            (Self::Square, TreeColumn::UpThenRight) => "┌",
+            (Self::Ascii, TreeColumn::UpThenRight) => "/",
+            (Self::Ascii, TreeColumn::DownAndRight) => "+",
+            (_, TreeColumn::DownAndRight) => "├",
+            (Self::Rounded, TreeColumn::DownThenRight) => "╰",
+            (Self::Square, TreeColumn::DownThenRight) => "└",
+            (Self::Ascii, TreeColumn::DownThenRight) => "`",
+        }
+    }
+    /// The connector between the last column and the node's text.
+    fn arm(&self) -> &'static str {
+        match self {
+            Self::Rounded => "╼\u{2009}",
+            Self::Square => "─\u{2009}",
+            Self::Ascii => "- ",
+        }
     }
 }
 
@@ -88,44 +404,63 @@ impl Formatter {
             .clone()
             .into_iter()
             .map(|x| match x {
-                TreeColumn::DownAndRight => TreeColumn::VerticalBar,
+                TreeColumn::DownAndRight | TreeColumn::UpThenRight => TreeColumn::VerticalBar,
                 TreeColumn::DownThenRight => TreeColumn::Empty,
                 x => x
             })
             .collect::<Vec<_>>();
         columns.push(TreeColumn::DownThenRight);
-        Self { columns, style: self.style }
+        self.with_columns(columns)
     }
     fn down_and_right(&self) -> Self {
         let mut columns = self.columns
             .clone()
             .into_iter()
             .map(|x| match x {
-                TreeColumn::DownAndRight => TreeColumn::VerticalBar,
+                TreeColumn::DownAndRight | TreeColumn::UpThenRight => TreeColumn::VerticalBar,
                 TreeColumn::DownThenRight => TreeColumn::Empty,
                 x => x
             })
             .collect::<Vec<_>>();
         columns.push(TreeColumn::DownAndRight);
-        Self { columns, style: self.style }
+        self.with_columns(columns)
     }
     fn up_then_right(&self) -> Self {
         let mut columns = self.columns
             .clone()
             .into_iter()
             .map(|x| match x {
-                TreeColumn::DownAndRight => TreeColumn::VerticalBar,
+                TreeColumn::DownAndRight | TreeColumn::UpThenRight => TreeColumn::VerticalBar,
                 TreeColumn::DownThenRight => TreeColumn::Empty,
                 x => x
             })
             .collect::<Vec<_>>();
         columns.push(TreeColumn::UpThenRight);
-        Self { columns, style: self.style }
+        self.with_columns(columns)
+    }
+    fn with_columns(&self, columns: Vec<TreeColumn>) -> Self {
+        Self { columns, metric: None, ..self.clone() }
     }
     fn with_column(&self, column: TreeColumn) -> Self {
         let mut columns = self.columns.clone();
         columns.push(column);
-        Self { columns, style: self.style }
+        self.with_columns(columns)
+    }
+    /// The formatter for the `ix`th child of the current node.
+    fn at(mut self, ix: usize) -> Self {
+        self.path.push(ix);
+        self
+    }
+    /// The formatter for a child with the given index, or for a line that
+    /// is not a child, see [`Row`].
+    fn at_row(self, index: Option<usize>) -> Self {
+        match index {
+            Some(ix) => self.at(ix),
+            None => Self { path: Vec::default(), ..self },
+        }
+    }
+    fn with_path(&self, path: Vec<usize>) -> Self {
+        Self { path, ..self.clone() }
     }
     fn replace_last_column(mut self, column: TreeColumn) -> Self {
         if let Some(last) = self.columns.last_mut() {
@@ -139,142 +474,516 @@ impl Formatter {
     }
     fn leading(&self) -> impl ToString {
         let depth = self.columns.len();
-        let thin_space = "\u{2009}";
         let leading = self.columns
             .iter()
             .enumerate()
-            .map(|(ix, c)| self.style.color(ix, c).to_string())
+            .map(|(ix, c)| self.style.color(ix, self.style.glyphs.column(c)).to_string())
             .collect::<Vec<_>>()
             .join("  ");
         let sep = if self.columns.is_empty() {
             String::default()
         } else {
-            let depth = if depth > 1 { depth - 1 } else { 0 };
-            self.style.color(depth, format!("╼{thin_space}")).to_string()
+            let depth = depth.saturating_sub(1);
+            self.style.color(depth, self.style.glyphs.arm()).to_string()
+        };
+        let leading = format!("{leading}{sep}");
+        if !self.style.use_color {
+            return leading
+        }
+        leading.dimmed().to_string()
+    }
+    /// A copy of the formatter with fresh per-render state for `roots`.
+    fn begin(&self, roots: &[PrettyTree]) -> Self {
+        let mut state = RenderState::default();
+        roots.iter().for_each(|x| state.count_shared(x));
+        let path = vec![0];
+        let state = Arc::new(Mutex::new(state));
+        Self { path, metric: None, state, ..self.clone() }
+    }
+    /// Renders `tree`, also returning where each node was printed.
+    pub(crate) fn format_lines(&self, tree: &PrettyTree) -> (String, Vec<NodeLine>) {
+        let formatter = self.begin(std::slice::from_ref(tree));
+        formatter.state().lines = Some(Vec::default());
+        let output = formatter.node(tree);
+        let lines = formatter.state().lines.take().unwrap_or_default();
+        (output, lines)
+    }
+    /// Renders with `render`, then adds the metric column and the gutter.
+    fn render_roots(&self, roots: &[PrettyTree], render: impl FnOnce(&Formatter) -> String) -> String {
+        let formatter = self.begin(roots);
+        let has_metrics = formatter.state().has_metrics;
+        if !has_metrics && self.style.gutter.is_none() {
+            return render(&formatter)
+        }
+        formatter.state().lines = Some(Vec::default());
+        let mut output = render(&formatter);
+        let lines = formatter.state().lines.take().unwrap_or_default();
+        if has_metrics {
+            output = formatter.with_metrics(&output, &lines);
+        }
+        if let Some(gutter) = self.style.gutter {
+            output = formatter.with_gutter(&output, &lines, gutter);
+        }
+        output
+    }
+    /// Appends each node's metric to its first line, right-aligned in a
+    /// column after the widest line.
+    fn with_metrics(&self, output: &str, lines: &[NodeLine]) -> String {
+        let mut metrics = Vec::default();
+        for line in lines {
+            metrics.push(line.metric);
+            metrics.extend((1..line.line_count).map(|_| None));
+        }
+        let texts = metrics.iter().map(|x| x.map(metric_text)).collect::<Vec<_>>();
+        let label_width = output.lines().map(display_width).max().unwrap_or_default();
+        let metric_width = texts.iter().flatten().map(|x| x.width()).max().unwrap_or_default();
+        let max = metrics.iter().flatten().fold(0.0, |max: f64, x| max.max(x.abs()));
+        let bar = match self.style.glyphs {
+            GlyphStyle::Ascii => "#",
+            _ => "█",
+        };
+        output
+            .lines()
+            .enumerate()
+            .map(|(ix, line)| {
+                let (Some(Some(value)), Some(Some(text))) = (metrics.get(ix), texts.get(ix)) else {
+                    return line.to_string()
+                };
+                let padding = " ".repeat(label_width - display_width(line));
+                let mut line = format!("{line}{padding}  {text:>metric_width$}");
+                if let Some(width) = self.style.metric_bars.filter(|_| max > 0.0) {
+                    let length = (value.abs() / max * width as f64).round() as usize;
+                    if length > 0 {
+                        line = format!("{line} {}", bar.repeat(length));
+                    }
+                }
+                line
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+    fn with_gutter(&self, output: &str, lines: &[NodeLine], gutter: Gutter) -> String {
+        let mut labels = Vec::default();
+        let mut index = 0;
+        for line in lines {
+            let label = match (gutter, &line.path) {
+                (Gutter::LineNumbers, _) | (_, None) => String::default(),
+                (Gutter::NodeIndex, Some(_)) => {
+                    index += 1;
+                    (index - 1).to_string()
+                }
+                (Gutter::Path, Some(path)) => path.iter().map(ToString::to_string).collect::<Vec<_>>().join("."),
+            };
+            labels.push(label);
+            labels.extend((1..line.line_count).map(|_| String::default()));
+        }
+        if gutter == Gutter::LineNumbers {
+            labels = (1..=output.lines().count()).map(|x| x.to_string()).collect();
+        }
+        let width = labels.iter().map(|x| x.width()).max().unwrap_or_default();
+        let bar = self.style.glyphs.column(&TreeColumn::VerticalBar);
+        output
+            .lines()
+            .enumerate()
+            .map(|(ix, line)| {
+                let label = labels.get(ix).map(String::as_str).unwrap_or_default();
+                let gutter = format!("{label:>width$} {bar}");
+                let gutter = if self.style.use_color { gutter.dimmed().to_string() } else { gutter };
+                format!("{gutter} {line}").trim_end().to_string()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+    fn state(&self) -> MutexGuard<'_, RenderState> {
+        self.state.lock().unwrap_or_else(|error| error.into_inner())
+    }
+    fn node(&self, tree: &PrettyTree) -> String {
+        if let Some(line) = self.inline(tree) {
+            return self.leaf(line)
+        }
+        match tree {
+            PrettyTree::Empty => String::default(),
+            PrettyTree::Value(x) => self.leaf(x),
+            PrettyTree::String(x) => self.leaf(format!("{x:?}")),
+            PrettyTree::Branch(x) => self.chain(x).unwrap_or_else(|| self.branch(&x.label, &x.children)),
+            PrettyTree::Fragment(x) => self.fragment(&x.nodes),
+            PrettyTree::Shared(x) => self.shared(x),
+            PrettyTree::Field(x) => self.field(x, 0),
+            PrettyTree::List(x) => self.list_node(x),
+            PrettyTree::Metric(x) => Self { metric: Some(x.value), ..self.clone() }.node(&x.node),
+        }
+    }
+    /// The one-line form of a branch or list, if inline mode is on and it
+    /// fits the width, node count and depth limits.
+    fn inline(&self, tree: &PrettyTree) -> Option<String> {
+        let width = self.style.inline_width?;
+        if !matches!(tree, PrettyTree::Branch(_) | PrettyTree::List(_)) {
+            return None
+        }
+        let mut nodes = 0;
+        let (line, height) = self.flat(tree, &mut nodes)?;
+        let depth = self.columns.len();
+        if nodes > self.style.inline_max_nodes || depth * 3 + line.width() > width {
+            return None
+        }
+        if self.style.max_depth.is_some_and(|max| depth + height > max) {
+            return None
+        }
+        Some(line)
+    }
+    /// A subtree as one line and its height, counting nodes into `nodes`.
+    /// Children are grouped and limited as in the tree layout. Fragments,
+    /// shared nodes, multi-line text and tables have no flat form.
+    fn flat(&self, tree: &PrettyTree, nodes: &mut usize) -> Option<(String, usize)> {
+        *nodes += 1;
+        if *nodes > self.style.inline_max_nodes {
+            return None
+        }
+        let children = |list: &[PrettyTree], nodes: &mut usize| -> Option<(Vec<String>, usize)> {
+            let list = flatten(list);
+            if self.tabulate(&list).is_some() {
+                return None
+            }
+            let rows = self.limit_children(self.group_siblings(indexed(&list)));
+            let mut height = 0;
+            let mut lines = Vec::with_capacity(rows.len());
+            for (_, child) in rows.iter() {
+                let (line, child_height) = self.flat(child, nodes)?;
+                height = height.max(child_height);
+                lines.push(line);
+            }
+            Some((lines, height + 1))
+        };
+        let line = match tree {
+            PrettyTree::Value(x) => x.to_string(),
+            PrettyTree::String(x) => format!("{x:?}"),
+            PrettyTree::Field(x) => x.to_string(),
+            PrettyTree::Branch(x) if x.children.is_empty() => x.label.to_string(),
+            PrettyTree::Branch(x) if x.children.iter().all(|x| matches!(x, PrettyTree::Field(_))) => {
+                let (fields, height) = children(&x.children, nodes)?;
+                return Some((format!("{} {{ {} }}", x.label, fields.join(", ")), height))
+            }
+            PrettyTree::Branch(x) => {
+                let (children, height) = children(&x.children, nodes)?;
+                return Some((format!("{}({})", x.label, children.join(", ")), height))
+            }
+            PrettyTree::List(x) => {
+                let header = x.data_type.as_deref().and_then(|x| self.style.type_headers.header(x));
+                let list = if self.style.sort_unordered { x.sorted_nodes() } else { Cow::Borrowed(&x.nodes[..]) };
+                let (items, height) = children(&list, nodes)?;
+                let items = items.join(", ");
+                let line = match header {
+                    Some(header) => format!("{header} [{items}]"),
+                    None => format!("[{items}]"),
+                };
+                return Some((line, height))
+            }
+            PrettyTree::Empty | PrettyTree::Fragment(_) | PrettyTree::Shared(_) | PrettyTree::Metric(_) => return None,
         };
-        format!("{leading}{sep}").dimmed()
+        if line.contains('\n') {
+            return None
+        }
+        Some((line, 1))
+    }
+    /// A branch and its chain of single children printed as one node, if
+    /// chain folding is on and the chain has at least two nodes. The chain
+    /// ends at a leaf, at a node with several children, or at the length
+    /// limit, where the rest of it continues on the next line.
+    fn chain(&self, branch: &PrettyBranch) -> Option<String> {
+        let max = self.style.fold_chains.or(self.style.compact_mode.then_some(usize::MAX))?;
+        let mut labels = vec![branch.label.to_string()];
+        let mut rest = &branch.children[..];
+        while let [child] = rest {
+            if labels.len() >= max {
+                break
+            }
+            let (label, children) = match child {
+                PrettyTree::Branch(x) => (x.label.to_string(), &x.children[..]),
+                PrettyTree::Value(x) => (x.to_string(), &[][..]),
+                PrettyTree::String(x) => (format!("{x:?}"), &[][..]),
+                PrettyTree::Field(x) => (x.to_string(), &[][..]),
+                _ => break,
+            };
+            if label.contains('\n') {
+                break
+            }
+            labels.push(label);
+            rest = children;
+        }
+        if labels.len() < 2 {
+            return None
+        }
+        let mut path = self.path.clone();
+        path.resize(path.len() + labels.len() - 1, 0);
+        Some(self.with_path(path).branch(labels.join(self.style.chain_separator), rest))
+    }
+    fn shared(&self, node: &PrettyShared) -> String {
+        let key = node.id();
+        let id = {
+            let mut state = self.state();
+            if let Some(id) = state.shared_ids.get(&key).copied() {
+                drop(state);
+                return self.leaf(format!("↑ ref #{id}"))
+            }
+            if state.shared_counts.get(&key).copied().unwrap_or_default() > 1 {
+                let id = state.shared_ids.len() + 1;
+                state.shared_ids.insert(key, id);
+                Some(id)
+            } else {
+                None
+            }
+        };
+        let tree = node.lock().clone();
+        match id {
+            Some(id) => self.node(&tree.with_marker(format!("#{id}"))),
+            None => self.node(&tree),
+        }
     }
     fn leaf(&self, value: impl ToString) -> String {
-        let value = value.to_string();
+        let value = self.truncate(value.to_string());
         let depth = self.columns.len();
+        self.record(Some(depth), &value);
         let leading = self.leading().to_string();
         let trailing = self.style.color(depth, value).to_string();
         format!("{leading}{trailing}")
     }
+    /// Prints `key: value` with the value padded to start after
+    /// `key_width` columns. Only the key is colored.
+    fn field(&self, field: &PrettyField, key_width: usize) -> String {
+        let padding = " ".repeat(key_width.saturating_sub(field.key.width()));
+        let text = self.truncate(format!("{}: {padding}{}", field.key, field.printed_value()));
+        let depth = self.columns.len();
+        self.record(Some(depth), &text);
+        let leading = self.leading().to_string();
+        let key_end = field.key.len() + 1;
+        if !self.style.use_color || !text.is_char_boundary(key_end) {
+            return format!("{leading}{}", self.style.color(depth, text).to_string())
+        }
+        let (key, value) = text.split_at(key_end);
+        format!("{leading}{}{value}", self.style.color(depth, key).to_string())
+    }
+    fn child(&self, child: &PrettyTree, key_width: usize) -> String {
+        match child {
+            PrettyTree::Field(x) => self.field(x, key_width),
+            child => self.node(child),
+        }
+    }
+    /// The width of the longest key among sibling fields, if aligned.
+    fn key_width(&self, rows: &[Row]) -> usize {
+        if !self.style.align_fields {
+            return 0
+        }
+        rows
+            .iter()
+            .filter_map(|(_, x)| match &**x {
+                PrettyTree::Field(x) => Some(x.key.width()),
+                _ => None,
+            })
+            .max()
+            .unwrap_or_default()
+    }
+    /// Notes a printed node, or with `depth` unset, a line between nodes.
+    fn record(&self, depth: Option<usize>, text: &str) {
+        if let Some(lines) = self.state().lines.as_mut() {
+            let line_count = text.lines().count().max(1);
+            let path = depth.map(|_| self.path.clone()).filter(|x| !x.is_empty());
+            let depth = depth.unwrap_or_default();
+            let metric = self.metric.filter(|_| path.is_some());
+            lines.push(NodeLine { depth, text: text.to_string(), line_count, path, metric });
+        }
+    }
+    /// Applies the width limit to a node's text. Each column takes three
+    /// characters: the glyph and two spaces, or the glyph and the arm.
+    fn truncate(&self, value: String) -> String {
+        let Some(max_width) = self.style.max_width else { return value };
+        let available = max_width.saturating_sub(self.columns.len() * 3).max(1);
+        if value.chars().count() <= available {
+            return value
+        }
+        let mut value = value.chars().take(available - 1).collect::<String>();
+        value.push('…');
+        value
+    }
+    /// Applies the table, sibling grouping and breadth options to a list
+    /// of children.
+    fn arrange<'a, 'b>(&self, children: &'a [PrettyTree<'b>]) -> Vec<Row<'a, 'b>> {
+        let rows = match self.tabulate(children) {
+            Some(table) => table.into_iter().enumerate().map(|(ix, x)| (ix.checked_sub(1), Cow::Owned(x))).collect(),
+            None => indexed(children),
+        };
+        self.limit_children(self.group_siblings(rows))
+    }
+    /// Applies the table option to a list of children. The first row is
+    /// the header, then one row per child.
+    fn tabulate(&self, children: &[PrettyTree]) -> Option<Vec<PrettyTree<'static>>> {
+        if !self.style.tables {
+            return None
+        }
+        let separator = self.style.glyphs.column(&TreeColumn::VerticalBar);
+        crate::table::tabulate(children, separator)
+    }
+    /// Applies the sibling grouping options to a list of children. A run
+    /// keeps the index of its first child.
+    // Shared nodes are hashed by identity, which locking them cannot change.
+    #[allow(clippy::mutable_key_type)]
+    fn group_siblings<'a, 'b>(&self, rows: Vec<Row<'a, 'b>>) -> Vec<Row<'a, 'b>> {
+        if !self.style.collapse_repeats && !self.style.back_references {
+            return rows
+        }
+        let mut grouped = Vec::with_capacity(rows.len());
+        // The printed position of the first occurrence of each child.
+        let mut positions = HashMap::<&PrettyTree, usize>::default();
+        let mut ix = 0;
+        while ix < rows.len() {
+            let (index, child) = &rows[ix];
+            let run = if self.style.collapse_repeats {
+                rows[ix..].iter().take_while(|(_, x)| x == child).count()
+            } else {
+                1
+            };
+            let position = grouped.len();
+            let first = *positions.entry(&**child).or_insert(position);
+            let label = match &**child {
+                PrettyTree::Branch(x) => Some(x.label.to_string()),
+                PrettyTree::List(x) => {
+                    let header = x.data_type.as_deref().filter(|_| !self.style.compact_mode);
+                    let header = header.and_then(|x| self.style.type_headers.header(x));
+                    Some(header.map_or_else(|| String::from("[]"), String::from))
+                }
+                _ => None,
+            };
+            let node = match label {
+                Some(label) if self.style.back_references && first < position => {
+                    Cow::Owned(PrettyTree::Value(format!("{label} (same as #{first})").into()))
+                }
+                _ => child.clone(),
+            };
+            if run > 1 {
+                grouped.push((*index, Cow::Owned(node.into_owned().with_marker(format!("×{run}")))));
+            } else {
+                grouped.push((*index, node));
+            }
+            ix += run;
+        }
+        grouped
+    }
+    /// Applies the breadth limit to a list of children.
+    fn limit_children<'a, 'b>(&self, mut rows: Vec<Row<'a, 'b>>) -> Vec<Row<'a, 'b>> {
+        if let Some(max) = self.style.max_children.filter(|max| rows.len() > *max) {
+            let more = rows.len() - max;
+            rows.truncate(max);
+            rows.push((None, Cow::Owned(PrettyTree::Value(format!("… {more} more").into()))));
+        }
+        rows
+    }
     fn branch(&self, label: impl ToString, children: &[PrettyTree]) -> String {
+        let children = flatten(children);
+        let children = &children[..];
         let label = self.leaf(label);
         if children.is_empty() {
             return label
         }
-        if children.len() == 1 {
-            let child = children
-                .first()
-                .unwrap()
-                .format(&self.down_then_right());
+        if self.style.max_depth.is_some_and(|max| self.columns.len() + 1 >= max) {
+            let child = self.down_then_right().at(0).leaf("…");
             return format!("{label}\n{child}")
         }
-        let child_count = children.len();
-        let last_child_index = child_count - 1;
-        let children = children
+        let rows = self.arrange(children);
+        if let [(index, child)] = rows.as_slice() {
+            let child = self.down_then_right().at_row(*index).node(child);
+            return format!("{label}\n{child}")
+        }
+        let key_width = self.key_width(&rows);
+        let last_child_index = rows.len() - 1;
+        let children = rows
             .iter()
             .enumerate()
-            .map(|(ix, child)| {
+            .map(|(ix, (index, child))| {
                 let is_first = ix == 0;
                 let is_last = ix == last_child_index;
                 if is_first {
-                    return child.format(&self.down_and_right())
+                    return self.down_and_right().at_row(*index).child(child, key_width)
                 }
                 if is_last {
-                    return child.format(&self.down_then_right())
+                    return self.down_then_right().at_row(*index).child(child, key_width)
                 }
-                return child.format(&self.down_and_right())
+                self.down_and_right().at_row(*index).child(child, key_width)
             })
             .collect::<Vec<_>>()
             .join("\n");
         format!("{label}\n{children}")
     }
+    fn list_node(&self, list: &PrettyList) -> String {
+        let header = list.data_type.as_deref().and_then(|x| self.style.type_headers.header(x));
+        let nodes = if self.style.sort_unordered { list.sorted_nodes() } else { Cow::Borrowed(&list.nodes[..]) };
+        self.list(header.as_deref(), &nodes)
+    }
+    /// A labeled list is a branch, or `label: []` when empty. Without a
+    /// label, or in compact mode, an empty list is `[]`, a single element
+    /// is printed in place of the list, and several elements hang off a
+    /// `╭` bracket on the list's own line.
     fn list(&self, label: Option<&str>, list: &[PrettyTree]) -> String {
+        let list = flatten(list);
+        let list = &list[..];
         let compact_mode = self.style.compact_mode;
-        let label = label.map(|x| self.leaf(x));
-        match label {
-            Some(label) if list.is_empty() => return label.to_owned(),
-            Some(label) if list.len() == 1 => {
-                let child = list
-                    .first()
-                    .unwrap()
-                    .format(&self.down_then_right());
-                return format!("{label}\n{child}")
-            }
-            None if list.len() == 1 => return list.first().unwrap().format(self),
-            None if list.is_empty() => return "[]".to_owned(),
-            _ => ()
-        }
-        match label {
-            Some(label) => {
-                let child_count = list.len();
-                let last_child_index = child_count - 1;
-                let children = list
-                    .iter()
-                    .enumerate()
-                    .map(|(ix, child)| {
-                        let is_first = ix == 0;
-                        let is_last = ix == last_child_index;
-                        if is_first {
-                            return child.format(&self.down_and_right())
-                        }
-                        if is_last {
-                            return child.format(&self.down_then_right())
-                        }
-                        return child.format(&self.down_and_right())
-                    })
-                    .collect::<Vec<_>>()
-                    .join("\n");
-                if compact_mode {
-                    format!("{children}")
-                } else {
-                    format!("{label}\n{children}")
-                }
-            }
-            None => {
-                let child_count = list.len();
-                let last_child_index = child_count - 1;
-                let children = list
-                    .iter()
-                    .enumerate()
-                    .map(|(ix, child)| {
-                        let is_first = ix == 0;
-                        let is_last = ix == last_child_index;
-                        if is_first {
-                            return child.format(&self.up_then_right())
-                        }
-                        if is_last {
-                            return child.format(&self.down_then_right())
-                        }
-                        return child.format(&self.down_and_right())
-                    })
-                    .collect::<Vec<_>>()
-                    .join("\n");
-                return children
-            }
+        match label.filter(|_| !compact_mode) {
+            Some(label) if list.is_empty() => return self.leaf(format!("{label}: []")),
+            Some(label) => return self.branch(label, list),
+            None if list.is_empty() => return self.leaf("[]"),
+            None if list.len() == 1 => return self.clone().at(0).node(list.first().unwrap()),
+            None => ()
         }
+        let rows = self.arrange(list);
+        let key_width = self.key_width(&rows);
+        let last_child_index = rows.len() - 1;
+        rows
+            .iter()
+            .enumerate()
+            .map(|(ix, (index, child))| {
+                let is_first = ix == 0;
+                let is_last = ix == last_child_index;
+                if is_first {
+                    return self.with_column(TreeColumn::UpThenRight).at_row(*index).child(child, key_width)
+                }
+                if is_last {
+                    return self.down_then_right().at_row(*index).child(child, key_width)
+                }
+                self.down_and_right().at_row(*index).child(child, key_width)
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
     }
-    // fn fragment(&self, list: &[PrettyTree]) -> String {
-    //     self.list(None, list)
-    // }
+    /// At the top level, a fragment is a forest: each of its nodes is
+    /// printed as a separate tree, and an empty fragment prints nothing.
+    /// Fragments among the children of a branch or list are spliced into
+    /// them, so only a fragment behind a shared node hangs off the
+    /// surrounding tree.
     fn fragment(&self, list: &[PrettyTree]) -> String {
-        if list.len() == 1 {
-            return list.first().unwrap().format(self);
+        let list = flatten(list);
+        let rows = self.limit_children(self.group_siblings(indexed(&list)));
+        if self.columns.is_empty() {
+            let mut trees = Vec::with_capacity(rows.len() * 2);
+            for (ix, (index, tree)) in rows.iter().enumerate() {
+                if let Some(separator) = self.style.forest_separator.filter(|_| ix > 0) {
+                    self.record(None, separator);
+                    trees.push(separator.to_string());
+                }
+                trees.push(self.with_path(Vec::default()).at_row(*index).node(tree));
+            }
+            return trees.join("\n")
         }
-        let child_count = list.len();
-        let last_child_index = if child_count == 0 { 0 } else { child_count - 1 };
-        list.iter()
+        if let [(index, child)] = rows.as_slice() {
+            return self.clone().at_row(*index).node(child);
+        }
+        let key_width = self.key_width(&rows);
+        let last_child_index = rows.len().saturating_sub(1);
+        rows.iter()
             .enumerate()
-            .map(|(ix, child)| {
+            .map(|(ix, (index, child))| {
                 let is_last = ix == last_child_index;
                 if is_last {
-                    child.format(&self.down_then_right())
+                    self.down_then_right().at_row(*index).child(child, key_width)
                 } else {
-                    child.format(&self.down_and_right())
+                    self.down_and_right().at_row(*index).child(child, key_width)
                 }
             })
             .collect::<Vec<_>>()
@@ -282,41 +991,91 @@ impl Formatter {
     }
 }
 
-impl PrettyTree {
+/// A child as printed, with its index among the node's children, or `None`
+/// for a line that is not one of them, such as a table header or a
+/// `… N more` leaf.
+type Row<'a, 'b> = (Option<usize>, Cow<'a, PrettyTree<'b>>);
+
+fn indexed<'a, 'b>(nodes: &'a [PrettyTree<'b>]) -> Vec<Row<'a, 'b>> {
+    nodes.iter().enumerate().map(|(ix, x)| (Some(ix), Cow::Borrowed(x))).collect()
+}
+
+/// Splices nested fragments into a list of nodes and drops empty nodes.
+fn flatten<'a, 'b>(nodes: &'a [PrettyTree<'b>]) -> Cow<'a, [PrettyTree<'b>]> {
+    if !nodes.iter().any(|x| matches!(x, PrettyTree::Empty | PrettyTree::Fragment(_))) {
+        return Cow::Borrowed(nodes)
+    }
+    Cow::Owned(crate::source::node_children(&PrettyTree::Fragment(PrettyFragment { nodes: nodes.to_vec() })))
+}
+
+impl PrettyTree<'_> {
     pub fn format(&self, formatter: &Formatter) -> String {
-        match self {
-            Self::Empty => String::default(),
-            Self::Value(x) => formatter.leaf(x),
-            Self::String(x) => formatter.leaf(format!("{x:?}")),
-            Self::Branch(x) => x.format(formatter),
-            Self::Fragment(x) => x.format(formatter),
-            // Self::List(x) => x.format(formatter),
+        if let Some(focus) = formatter.focus.as_ref() {
+            let formatter = formatter.clone().focus(None);
+            return match focus {
+                Focus::Ancestry(predicate) => self.ancestry(&**predicate).format(&formatter),
+                Focus::Inverted(predicate) => self.inverted(&**predicate).format(&formatter),
+            }
+        }
+        if formatter.style.layout != Layout::Indented {
+            return crate::layout::render(self, &formatter.style)
         }
+        formatter.render_roots(std::slice::from_ref(self), |x| x.node(self))
     }
     pub fn render(&self) -> String {
         self.format(&Default::default())
     }
+    /// Appends a marker such as `×3` to the node's own line.
+    fn with_marker(self, marker: impl AsRef<str>) -> Self {
+        let marker = marker.as_ref();
+        match self {
+            Self::Value(x) => Self::Value(format!("{x} {marker}").into()),
+            Self::String(x) => Self::Value(format!("{x:?} {marker}").into()),
+            Self::Branch(mut x) => {
+                x.label = format!("{} {marker}", x.label).into();
+                Self::Branch(x)
+            }
+            Self::List(mut x) => {
+                x.data_type = x.data_type.map(|label| format!("{label} {marker}").into());
+                Self::List(x)
+            }
+            Self::Field(x) => {
+                let value = format!("{} {marker}", x.printed_value());
+                Self::Field(PrettyField::new(x.key, value))
+            }
+            Self::Metric(mut x) => {
+                x.node = Box::new(x.node.with_marker(marker));
+                Self::Metric(x)
+            }
+            x => x,
+        }
+    }
 }
-impl PrettyBranch {
+impl PrettyBranch<'_> {
     pub fn format(&self, formatter: &Formatter) -> String {
-        formatter.branch(&self.label, &self.children)
+        if !formatter.is_plain() {
+            return PrettyTree::Branch(self.clone()).format(formatter)
+        }
+        formatter.render_roots(&self.children, |x| x.branch(&self.label, &self.children))
+    }
+}
+impl PrettyFragment<'_> {
+    pub fn format(&self, formatter: &Formatter) -> String {
+        if !formatter.is_plain() {
+            return PrettyTree::Fragment(self.clone()).format(formatter)
+        }
+        formatter.render_roots(&self.nodes, |x| x.fragment(&self.nodes))
     }
 }
-impl PrettyFragment {
+impl PrettyList<'_> {
     pub fn format(&self, formatter: &Formatter) -> String {
-        formatter.fragment(&self.nodes)
+        if !formatter.is_plain() {
+            return PrettyTree::List(self.clone()).format(formatter)
+        }
+        formatter.render_roots(&self.nodes, |x| x.list_node(self))
     }
 }
-// impl PrettyList {
-//     pub fn format(&self, formatter: &Formatter) -> String {
-//         // if let Some(name) = self.name.as_ref() {
-//         //     return formatter.branch(name, &self.nodes)
-//         // }
-//         // return formatter.fragment(&self.nodes)
-//         formatter.list(self.data_type.as_ref().map(|x| x.as_str()), &self.nodes)
-//     }
-// }
-impl std::fmt::Display for PrettyTree {
+impl std::fmt::Display for PrettyTree<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.format(&Default::default()))
     }
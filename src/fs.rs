@@ -0,0 +1,218 @@
+//! Directory listings, like `tree(1)`.
+
+use std::fs::Metadata;
+use std::io;
+use std::path::Path;
+
+use crate::PrettyTree;
+
+/// What [`dir_tree`] lists and how it labels entries.
+#[derive(Debug, Clone)]
+pub struct DirTreeOptions {
+    show_hidden: bool,
+    exclude: Vec<String>,
+    max_depth: Option<usize>,
+    sizes: bool,
+    permissions: bool,
+    dirs_first: bool,
+}
+
+impl Default for DirTreeOptions {
+    fn default() -> Self {
+        Self {
+            show_hidden: false,
+            exclude: Vec::default(),
+            max_depth: None,
+            sizes: false,
+            permissions: false,
+            dirs_first: true,
+        }
+    }
+}
+
+impl DirTreeOptions {
+    /// List entries whose name starts with `.`.
+    pub fn show_hidden(self, show_hidden: bool) -> Self {
+        Self { show_hidden, ..self }
+    }
+    /// Skip entries matching a gitignore-style glob. Patterns without a
+    /// `/` match the entry's name, others its path relative to the root.
+    /// `*` and `?` do not cross `/`, `**` does, and a trailing `/` only
+    /// matches directories.
+    pub fn exclude(mut self, pattern: impl Into<String>) -> Self {
+        self.exclude.push(pattern.into());
+        self
+    }
+    /// List at most this many levels below the root; deeper directories
+    /// get a `…` child.
+    pub fn max_depth(self, max_depth: Option<usize>) -> Self {
+        Self { max_depth, ..self }
+    }
+    /// Annotate files with their size, e.g. `[1.2 KiB]`.
+    pub fn sizes(self, sizes: bool) -> Self {
+        Self { sizes, ..self }
+    }
+    /// Annotate entries with their permissions, e.g. `[rw-r--r--]`.
+    pub fn permissions(self, permissions: bool) -> Self {
+        Self { permissions, ..self }
+    }
+    /// List directories before files. On by default; entries are otherwise
+    /// sorted by name.
+    pub fn dirs_first(self, dirs_first: bool) -> Self {
+        Self { dirs_first, ..self }
+    }
+}
+
+/// Walks `path` into a tree labeled with entry names. Directories end in
+/// `/`, symlinks are shown as `name -> target` and not followed.
+/// Directories that cannot be read get an `<error: ..>` child.
+pub fn dir_tree(path: impl AsRef<Path>, options: &DirTreeOptions) -> io::Result<PrettyTree<'static>> {
+    let path = path.as_ref();
+    let metadata = std::fs::symlink_metadata(path)?;
+    let label = annotate(path.display().to_string(), &metadata, options);
+    if !metadata.is_dir() {
+        return Ok(PrettyTree::Value(label.into()))
+    }
+    Ok(PrettyTree::branch_of(label, children(path, "", 0, options)))
+}
+
+struct Entry {
+    name: String,
+    relative: String,
+    metadata: Metadata,
+}
+
+fn children(dir: &Path, relative: &str, depth: usize, options: &DirTreeOptions) -> Vec<PrettyTree<'static>> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(error) => return vec![PrettyTree::Value(format!("<error: {error}>").into())],
+    };
+    let mut entries = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let metadata = entry.path().symlink_metadata().ok()?;
+            let relative = format!("{relative}{name}");
+            Some(Entry { name, relative, metadata })
+        })
+        .filter(|x| options.show_hidden || !x.name.starts_with('.'))
+        .filter(|x| !options.exclude.iter().any(|pattern| excluded(pattern, x)))
+        .collect::<Vec<_>>();
+    if options.max_depth.is_some_and(|max| depth >= max) {
+        if entries.is_empty() {
+            return Vec::default()
+        }
+        return vec![PrettyTree::leaf("…")]
+    }
+    entries.sort_by(|a, b| {
+        let dirs_first = match options.dirs_first {
+            true => b.metadata.is_dir().cmp(&a.metadata.is_dir()),
+            false => std::cmp::Ordering::Equal,
+        };
+        dirs_first.then_with(|| a.name.cmp(&b.name))
+    });
+    entries
+        .into_iter()
+        .map(|entry| {
+            let path = dir.join(&entry.name);
+            if entry.metadata.is_dir() {
+                let label = annotate(format!("{}/", entry.name), &entry.metadata, options);
+                let relative = format!("{}/", entry.relative);
+                return PrettyTree::branch_of(label, children(&path, &relative, depth + 1, options))
+            }
+            let mut name = entry.name;
+            if entry.metadata.is_symlink() {
+                if let Ok(target) = std::fs::read_link(&path) {
+                    name = format!("{name} -> {}", target.display());
+                }
+            }
+            PrettyTree::Value(annotate(name, &entry.metadata, options).into())
+        })
+        .collect()
+}
+
+fn annotate(label: String, metadata: &Metadata, options: &DirTreeOptions) -> String {
+    let mut annotations = Vec::default();
+    if options.permissions {
+        annotations.push(permissions(metadata));
+    }
+    if options.sizes && metadata.is_file() {
+        annotations.push(size(metadata.len()));
+    }
+    if annotations.is_empty() {
+        return label
+    }
+    format!("{label} [{}]", annotations.join(" "))
+}
+
+#[cfg(unix)]
+fn permissions(metadata: &Metadata) -> String {
+    use std::os::unix::fs::PermissionsExt;
+    let mode = metadata.permissions().mode();
+    let flags = ['r', 'w', 'x'];
+    (0..9)
+        .map(|ix| match mode & (0o400 >> ix) {
+            0 => '-',
+            _ => flags[ix % 3],
+        })
+        .collect()
+}
+
+#[cfg(not(unix))]
+fn permissions(metadata: &Metadata) -> String {
+    match metadata.permissions().readonly() {
+        true => String::from("readonly"),
+        false => String::from("writable"),
+    }
+}
+
+fn size(bytes: u64) -> String {
+    let units = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < units.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    match unit {
+        0 => format!("{bytes} B"),
+        _ => format!("{size:.1} {}", units[unit]),
+    }
+}
+
+fn excluded(pattern: &str, entry: &Entry) -> bool {
+    let (pattern, dir_only) = match pattern.strip_suffix('/') {
+        Some(pattern) => (pattern, true),
+        None => (pattern, false),
+    };
+    if dir_only && !entry.metadata.is_dir() {
+        return false
+    }
+    match pattern.strip_prefix('/') {
+        Some(pattern) => glob_match(pattern, &entry.relative),
+        None if pattern.contains('/') => glob_match(pattern, &entry.relative),
+        None => glob_match(pattern, &entry.name),
+    }
+}
+
+fn glob_match(pattern: &str, text: &str) -> bool {
+    fn matches(pattern: &[char], text: &[char]) -> bool {
+        match pattern {
+            [] => text.is_empty(),
+            ['*', '*', '/', rest @ ..] => {
+                matches(rest, text) || (0..text.len()).any(|ix| text[ix] == '/' && matches(rest, &text[ix + 1..]))
+            }
+            ['*', '*', rest @ ..] => (0..=text.len()).any(|ix| matches(rest, &text[ix..])),
+            ['*', rest @ ..] => {
+                (0..=text.len())
+                    .take_while(|ix| *ix == 0 || text[ix - 1] != '/')
+                    .any(|ix| matches(rest, &text[ix..]))
+            }
+            ['?', rest @ ..] => text.first().is_some_and(|x| *x != '/') && matches(rest, &text[1..]),
+            [char, rest @ ..] => text.first() == Some(char) && matches(rest, &text[1..]),
+        }
+    }
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let text = text.chars().collect::<Vec<_>>();
+    matches(&pattern, &text)
+}
@@ -1,15 +1,47 @@
-use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::borrow::Cow;
+use std::cell::{Cell, RefCell};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet, LinkedList, VecDeque};
+use std::num::{
+    NonZeroI8, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI128, NonZeroIsize,
+    NonZeroU8, NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU128, NonZeroUsize,
+};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::{Arc, Mutex, RwLock, TryLockError};
+use std::time::Duration;
 
-use crate::PrettyBranch;
+use crate::{PrettyBranch, PrettyFragment, PrettyList, PrettyShared};
 
 use super::PrettyTree;
 
+/// Conversion into a [`PrettyTree`], which may borrow from `self`.
+///
+/// The implementations for std types produce these shapes:
+///
+/// - Integers, `NonZero*` and `bool` are leaves printed with `Display`
+///   (`42`); floats, `char`, `Duration` and `Ordering` are leaves printed
+///   with `Debug` (`1.0`, `'x'`, `1.5s`, `Less`).
+/// - `str`, `String`, `Path` and `PathBuf` are quoted leaves (`"text"`).
+/// - `Box`, `Rc`, `Arc`, `Cow` and references are transparent. So are
+///   `Cell`, `RefCell`, `Mutex` and `RwLock`; the locks are only tried, and
+///   print `<borrowed>` or `<locked>` when the value is unavailable.
+/// - `Option` is transparent for `Some` and prints `None` otherwise;
+///   `Result` is an `Ok` or `Err` branch with the value as its child.
+/// - Sequences, sets, maps and tuples of up to 12 elements are lists
+///   headed by their type name, shortened or hidden according to
+///   [`TypeHeaders`](crate::TypeHeaders). Each map entry is a branch
+///   labeled with the key. Empty collections print as `Type: []`.
+///   `HashMap` and `HashSet` are printed sorted by key or element text,
+///   with runs of digits compared as numbers, unless
+///   [`FormatterStyle::sort_unordered`](crate::FormatterStyle::sort_unordered)
+///   is turned off.
 pub trait ToPrettyTree {
-    fn to_pretty_tree(&self) -> PrettyTree;
+    fn to_pretty_tree(&self) -> PrettyTree<'_>;
 }
 
-impl ToPrettyTree for PrettyBranch {
-    fn to_pretty_tree(&self) -> PrettyTree {
+impl ToPrettyTree for PrettyBranch<'_> {
+    fn to_pretty_tree(&self) -> PrettyTree<'_> {
         PrettyTree::Branch(self.clone())
     }
 }
@@ -19,192 +51,302 @@ pub trait PrettyTreePrinter {
 }
 
 impl ToPrettyTree for () {
-    fn to_pretty_tree(&self) -> PrettyTree {
-        PrettyTree::Value(String::from("()"))
+    fn to_pretty_tree(&self) -> PrettyTree<'_> {
+        PrettyTree::leaf("()")
     }
 }
 impl ToPrettyTree for bool {
-    fn to_pretty_tree(&self) -> PrettyTree {
+    fn to_pretty_tree(&self) -> PrettyTree<'_> {
         match self {
-            true => PrettyTree::Value(String::from("true")),
-            false => PrettyTree::Value(String::from("false")),
+            true => PrettyTree::leaf("true"),
+            false => PrettyTree::leaf("false"),
         }
     }
 }
-impl ToPrettyTree for usize {
-    fn to_pretty_tree(&self) -> PrettyTree {
-        PrettyTree::Value(format!("{}", self))
-    }
-}
-impl ToPrettyTree for u8 {
-    fn to_pretty_tree(&self) -> PrettyTree {
-        PrettyTree::Value(format!("{}", self))
-    }
+
+/// Leaves printed with `Display`, which can also be passed by value.
+macro_rules! display_leaf {
+    ($($type:ty),* $(,)?) => {$(
+        impl ToPrettyTree for $type {
+            fn to_pretty_tree(&self) -> PrettyTree<'_> {
+                PrettyTree::value(self)
+            }
+        }
+        impl From<$type> for PrettyTree<'_> {
+            fn from(value: $type) -> Self { PrettyTree::value(value) }
+        }
+    )*};
 }
-impl ToPrettyTree for u32 {
-    fn to_pretty_tree(&self) -> PrettyTree {
-        PrettyTree::Value(format!("{}", self))
-    }
+
+/// Leaves printed with `Debug`, which can also be passed by value.
+macro_rules! debug_leaf {
+    ($($type:ty),* $(,)?) => {$(
+        impl ToPrettyTree for $type {
+            fn to_pretty_tree(&self) -> PrettyTree<'_> {
+                PrettyTree::Value(format!("{self:?}").into())
+            }
+        }
+        impl From<$type> for PrettyTree<'_> {
+            fn from(value: $type) -> Self { PrettyTree::Value(format!("{value:?}").into()) }
+        }
+    )*};
 }
-impl<Type> PrettyTreePrinter for Type where Type: ToPrettyTree {
+
+display_leaf!(
+    u8, u16, u32, u64, u128, usize,
+    i8, i16, i32, i64, i128, isize,
+    NonZeroU8, NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU128, NonZeroUsize,
+    NonZeroI8, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI128, NonZeroIsize,
+);
+debug_leaf!(f32, f64, char, Duration, Ordering);
+
+impl<Type> PrettyTreePrinter for Type where Type: ToPrettyTree + ?Sized {
     fn print_pretty_tree(&self) {
         let tree = self.to_pretty_tree().format(&Default::default());
         println!("{tree}")
     }
 }
 
-impl ToPrettyTree for PrettyTree {
-    fn to_pretty_tree(&self) -> PrettyTree { self.clone() }
+impl ToPrettyTree for PrettyTree<'_> {
+    fn to_pretty_tree(&self) -> PrettyTree<'_> { self.clone() }
 }
 impl ToPrettyTree for String {
-    fn to_pretty_tree(&self) -> PrettyTree { PrettyTree::string(self) }
+    fn to_pretty_tree(&self) -> PrettyTree<'_> { PrettyTree::String(Cow::Borrowed(self)) }
+}
+impl<T> ToPrettyTree for &T where T: ToPrettyTree + ?Sized {
+    fn to_pretty_tree(&self) -> PrettyTree<'_> { (*self).to_pretty_tree() }
 }
-impl<T> ToPrettyTree for &T where T: ToPrettyTree {
-    fn to_pretty_tree(&self) -> PrettyTree { (*self).to_pretty_tree() }
+impl<T> ToPrettyTree for Box<T> where T: ToPrettyTree + ?Sized {
+    fn to_pretty_tree(&self) -> PrettyTree<'_> { (**self).to_pretty_tree() }
+}
+impl<T> ToPrettyTree for Rc<T> where T: ToPrettyTree + ?Sized {
+    fn to_pretty_tree(&self) -> PrettyTree<'_> { (**self).to_pretty_tree() }
+}
+impl<T> ToPrettyTree for Arc<T> where T: ToPrettyTree + ?Sized {
+    fn to_pretty_tree(&self) -> PrettyTree<'_> { (**self).to_pretty_tree() }
+}
+impl<T> ToPrettyTree for Cow<'_, T> where T: ToPrettyTree + ToOwned + ?Sized {
+    fn to_pretty_tree(&self) -> PrettyTree<'_> { (**self).to_pretty_tree() }
 }
 // impl<Key, Value> ToPrettyTree for (Key, Value) where Key: ToString, Value: ToPrettyTree {
-//     fn to_pretty_tree(&self) -> PrettyTree {
+//     fn to_pretty_tree(&self) -> PrettyTree<'_> {
 //         PrettyTree::branch_of(self.0.to_string(), &[ self.1.to_pretty_tree() ])
 //     }
 // }
-impl ToPrettyTree for &str {
-    fn to_pretty_tree(&self) -> PrettyTree {
-        PrettyTree::string(self)
+impl ToPrettyTree for str {
+    fn to_pretty_tree(&self) -> PrettyTree<'_> {
+        PrettyTree::String(Cow::Borrowed(self))
+    }
+}
+impl ToPrettyTree for Path {
+    fn to_pretty_tree(&self) -> PrettyTree<'_> {
+        PrettyTree::String(self.to_string_lossy())
+    }
+}
+impl ToPrettyTree for PathBuf {
+    fn to_pretty_tree(&self) -> PrettyTree<'_> {
+        self.as_path().to_pretty_tree()
     }
 }
 impl<T: ToPrettyTree> ToPrettyTree for Option<T> {
-    fn to_pretty_tree(&self) -> PrettyTree {
+    fn to_pretty_tree(&self) -> PrettyTree<'_> {
         match self {
-            Self::None => PrettyTree::Value(String::from("None")),
+            Self::None => PrettyTree::leaf("None"),
             Self::Some(x) => x.to_pretty_tree(),
         }
     }
 }
-impl<T: ToPrettyTree> ToPrettyTree for Vec<T> {
-    fn to_pretty_tree(&self) -> PrettyTree {
-        let name = format!(
-            "Vec<{}>",
-            std::any::type_name::<T>()
-        );
-        let children = self.iter().map(ToPrettyTree::to_pretty_tree).collect::<Vec<_>>();
-        if children.is_empty() {
-            return PrettyTree::key_value(name, PrettyTree::Value(String::from("[]")))
+impl<T: ToPrettyTree, E: ToPrettyTree> ToPrettyTree for Result<T, E> {
+    fn to_pretty_tree(&self) -> PrettyTree<'_> {
+        match self {
+            Self::Ok(x) => PrettyTree::branch_of("Ok", [x.to_pretty_tree()]),
+            Self::Err(x) => PrettyTree::branch_of("Err", [x.to_pretty_tree()]),
         }
-        PrettyTree::branch_of(name, &children)
-        // PrettyTree::List(crate::PrettyList {
-        //     name: Some(name),
-        //     nodes: children,
-        // })
     }
 }
-impl<T: ToPrettyTree> ToPrettyTree for &[T] {
-    fn to_pretty_tree(&self) -> PrettyTree {
-        let name = std::any::type_name::<Self>();
-        let children = self.iter().map(ToPrettyTree::to_pretty_tree).collect::<Vec<_>>();
-        PrettyTree::branch_of(name, &children)
+
+impl<T: ToPrettyTree + Copy> ToPrettyTree for Cell<T> {
+    fn to_pretty_tree(&self) -> PrettyTree<'_> {
+        self.get().to_pretty_tree().into_owned()
+    }
+}
+impl<T: ToPrettyTree + ?Sized> ToPrettyTree for RefCell<T> {
+    fn to_pretty_tree(&self) -> PrettyTree<'_> {
+        match self.try_borrow() {
+            Ok(x) => x.to_pretty_tree().into_owned(),
+            Err(_) => PrettyTree::leaf("<borrowed>"),
+        }
     }
 }
+impl<T: ToPrettyTree + ?Sized> ToPrettyTree for Mutex<T> {
+    fn to_pretty_tree(&self) -> PrettyTree<'_> {
+        match self.try_lock() {
+            Ok(x) => x.to_pretty_tree().into_owned(),
+            Err(TryLockError::Poisoned(x)) => x.into_inner().to_pretty_tree().into_owned(),
+            Err(TryLockError::WouldBlock) => PrettyTree::leaf("<locked>"),
+        }
+    }
+}
+impl<T: ToPrettyTree + ?Sized> ToPrettyTree for RwLock<T> {
+    fn to_pretty_tree(&self) -> PrettyTree<'_> {
+        match self.try_read() {
+            Ok(x) => x.to_pretty_tree().into_owned(),
+            Err(TryLockError::Poisoned(x)) => x.into_inner().to_pretty_tree().into_owned(),
+            Err(TryLockError::WouldBlock) => PrettyTree::leaf("<locked>"),
+        }
+    }
+}
+
+/// A list headed by the type name of `Type`, with one child per item.
+fn sequence<'a, Type: ?Sized, T: ToPrettyTree + 'a>(
+    items: impl IntoIterator<Item = &'a T>,
+) -> PrettyTree<'a> {
+    PrettyTree::list_of::<Type, _>(items.into_iter().map(ToPrettyTree::to_pretty_tree))
+}
+
+/// A list headed by the type name of `Type`, with one `key` branch per entry.
+fn entries<'a, Type: ?Sized, Key: ToString + 'a, Value: ToPrettyTree + 'a>(
+    entries: impl IntoIterator<Item = (&'a Key, &'a Value)>,
+) -> PrettyTree<'a> {
+    PrettyTree::list_of::<Type, _>(entries.into_iter().map(entry))
+}
+
+fn entry<'a, Key: ToString, Value: ToPrettyTree>((key, value): (&Key, &'a Value)) -> PrettyTree<'a> {
+    PrettyTree::branch_of(key.to_string(), [ value.to_pretty_tree() ])
+}
+
+impl<T: ToPrettyTree> ToPrettyTree for Vec<T> {
+    fn to_pretty_tree(&self) -> PrettyTree<'_> {
+        sequence::<Self, _>(self)
+    }
+}
+impl<T: ToPrettyTree> ToPrettyTree for VecDeque<T> {
+    fn to_pretty_tree(&self) -> PrettyTree<'_> {
+        sequence::<Self, _>(self)
+    }
+}
+impl<T: ToPrettyTree> ToPrettyTree for LinkedList<T> {
+    fn to_pretty_tree(&self) -> PrettyTree<'_> {
+        sequence::<Self, _>(self)
+    }
+}
+/// Elements are listed in the heap's internal order, not sorted.
+impl<T: ToPrettyTree> ToPrettyTree for BinaryHeap<T> {
+    fn to_pretty_tree(&self) -> PrettyTree<'_> {
+        sequence::<Self, _>(self)
+    }
+}
+impl<T: ToPrettyTree, const N: usize> ToPrettyTree for [T; N] {
+    fn to_pretty_tree(&self) -> PrettyTree<'_> {
+        sequence::<Self, _>(self)
+    }
+}
+impl<T: ToPrettyTree> ToPrettyTree for [T] {
+    fn to_pretty_tree(&self) -> PrettyTree<'_> {
+        sequence::<Self, _>(self)
+    }
+}
+/// Entries are marked unordered, see [`PrettyList::unordered`](crate::PrettyList::unordered).
 impl<Key: ToString, Value: ToPrettyTree> ToPrettyTree for HashMap<Key, Value> {
-    fn to_pretty_tree(&self) -> PrettyTree {
-        let name = std::any::type_name::<Self>();
-        let children = self
-            .iter()
-            .map(|(key, value)| {
-                PrettyTree::branch_of(key.to_string(), &[ value.to_pretty_tree() ])
-            })
-            .collect::<Vec<_>>();
-        PrettyTree::branch_of(name, &children)
+    fn to_pretty_tree(&self) -> PrettyTree<'_> {
+        PrettyTree::unordered_list_of::<Self, _>(self.iter().map(entry))
     }
 }
 impl<Key: ToString, Value: ToPrettyTree> ToPrettyTree for BTreeMap<Key, Value> {
-    fn to_pretty_tree(&self) -> PrettyTree {
-        let name = std::any::type_name::<Self>();
-        let children = self
-            .iter()
-            .map(|(key, value)| {
-                PrettyTree::branch_of(key.to_string(), &[ value.to_pretty_tree() ])
-            })
-            .collect::<Vec<_>>();
-        PrettyTree::branch_of(name, &children)
+    fn to_pretty_tree(&self) -> PrettyTree<'_> {
+        entries::<Self, _, _>(self)
     }
 }
+/// Elements are marked unordered, see [`PrettyList::unordered`](crate::PrettyList::unordered).
 impl<T: ToPrettyTree> ToPrettyTree for HashSet<T> {
-    fn to_pretty_tree(&self) -> PrettyTree {
-        let name = std::any::type_name::<Self>();
-        let children = self.iter().map(ToPrettyTree::to_pretty_tree).collect::<Vec<_>>();
-        PrettyTree::branch_of(name, &children)
+    fn to_pretty_tree(&self) -> PrettyTree<'_> {
+        PrettyTree::unordered_list_of::<Self, _>(self.iter().map(ToPrettyTree::to_pretty_tree))
     }
 }
 impl<T: ToPrettyTree> ToPrettyTree for BTreeSet<T> {
-    fn to_pretty_tree(&self) -> PrettyTree {
-        let name = std::any::type_name::<Self>();
-        let children = self.iter().map(ToPrettyTree::to_pretty_tree).collect::<Vec<_>>();
-        PrettyTree::branch_of(name, &children)
+    fn to_pretty_tree(&self) -> PrettyTree<'_> {
+        sequence::<Self, _>(self)
     }
 }
 
-impl<A: ToPrettyTree, B: ToPrettyTree> ToPrettyTree for (A, B) {
-    fn to_pretty_tree(&self) -> PrettyTree {
-        let name = format!(
-            "({}, {})",
-            std::any::type_name::<A>(),
-            std::any::type_name::<B>(),
-        );
-        PrettyTree::branch_of(name, &[
-            self.0.to_pretty_tree(),
-            self.1.to_pretty_tree(),
-        ])
-    }
-}
-impl<A: ToPrettyTree, B: ToPrettyTree, C: ToPrettyTree> ToPrettyTree for (A, B, C) {
-    fn to_pretty_tree(&self) -> PrettyTree {
-        let name = format!(
-            "({}, {})",
-            std::any::type_name::<A>(),
-            std::any::type_name::<B>(),
-        );
-        PrettyTree::branch_of(name, &[
-            self.0.to_pretty_tree(),
-            self.1.to_pretty_tree(),
-            self.2.to_pretty_tree(),
-        ])
-    }
+macro_rules! tuple {
+    ($($name:ident . $index:tt),+) => {
+        impl<$($name: ToPrettyTree),+> ToPrettyTree for ($($name,)+) {
+            fn to_pretty_tree(&self) -> PrettyTree<'_> {
+                PrettyTree::list_of::<Self, _>([$(self.$index.to_pretty_tree()),+])
+            }
+        }
+    };
 }
 
+tuple!(A.0);
+tuple!(A.0, B.1);
+tuple!(A.0, B.1, C.2);
+tuple!(A.0, B.1, C.2, D.3);
+tuple!(A.0, B.1, C.2, D.3, E.4);
+tuple!(A.0, B.1, C.2, D.3, E.4, F.5);
+tuple!(A.0, B.1, C.2, D.3, E.4, F.5, G.6);
+tuple!(A.0, B.1, C.2, D.3, E.4, F.5, G.6, H.7);
+tuple!(A.0, B.1, C.2, D.3, E.4, F.5, G.6, H.7, I.8);
+tuple!(A.0, B.1, C.2, D.3, E.4, F.5, G.6, H.7, I.8, J.9);
+tuple!(A.0, B.1, C.2, D.3, E.4, F.5, G.6, H.7, I.8, J.9, K.10);
+tuple!(A.0, B.1, C.2, D.3, E.4, F.5, G.6, H.7, I.8, J.9, K.10, L.11);
+
 #[cfg(feature = "serde_json")]
 impl ToPrettyTree for serde_json::Value {
-    fn to_pretty_tree(&self) -> PrettyTree {
+    fn to_pretty_tree(&self) -> PrettyTree<'_> {
         match self {
             Self::Null => PrettyTree::str("Null"),
             Self::Bool(x) => PrettyTree::string(format!("Bool({x})")),
             Self::Number(x) => PrettyTree::string(format!("Number({x})")),
             Self::String(x) => PrettyTree::string(format!("String({x})")),
-            Self::Array(xs) => PrettyTree::some_branch(PrettyBranch::from_iter("Array", xs.clone())),
-            Self::Object(xs) => PrettyTree::some_branch(PrettyBranch::from_iter("Object", xs)),
+            Self::Array(xs) => PrettyTree::some_branch(PrettyBranch::from_iter("Array", xs)),
+            Self::Object(xs) => {
+                let entries = xs.iter().map(|entry| entry.to_pretty_tree().into_owned());
+                PrettyTree::some_branch(PrettyBranch::from_iter("Object", entries))
+            }
         }
     }
 }
 
 #[cfg(feature = "indexmap")]
 impl<Key: ToString, Value: ToPrettyTree> ToPrettyTree for indexmap::IndexMap<Key, Value> {
-    fn to_pretty_tree(&self) -> PrettyTree {
-        let name = std::any::type_name::<Self>();
-        let children = self
-            .iter()
-            .map(|(key, value)| (key.to_string(), value.to_pretty_tree()))
-            .collect::<Vec<_>>();
-        PrettyTree::branch_of(name, children)
+    fn to_pretty_tree(&self) -> PrettyTree<'_> {
+        entries::<Self, _, _>(self)
     }
 }
 
 #[cfg(feature = "indexmap")]
 impl<Type: ToPrettyTree> ToPrettyTree for indexmap::IndexSet<Type> {
-    fn to_pretty_tree(&self) -> PrettyTree {
-        let name = std::any::type_name::<Self>();
-        let children = self
-            .iter()
-            .map(|x| x.to_pretty_tree())
-            .collect::<Vec<_>>();
-        PrettyTree::branch_of(name, children)
+    fn to_pretty_tree(&self) -> PrettyTree<'_> {
+        sequence::<Self, _>(self)
     }
 }
+
+impl<'a, T: ToPrettyTree + ?Sized> From<&'a T> for PrettyTree<'a> {
+    fn from(value: &'a T) -> Self { value.to_pretty_tree() }
+}
+impl<'a> From<PrettyBranch<'a>> for PrettyTree<'a> {
+    fn from(value: PrettyBranch<'a>) -> Self { PrettyTree::Branch(value) }
+}
+impl<'a> From<PrettyFragment<'a>> for PrettyTree<'a> {
+    fn from(value: PrettyFragment<'a>) -> Self { PrettyTree::Fragment(value) }
+}
+impl<'a> From<PrettyList<'a>> for PrettyTree<'a> {
+    fn from(value: PrettyList<'a>) -> Self { PrettyTree::List(value) }
+}
+impl From<PrettyShared> for PrettyTree<'_> {
+    fn from(value: PrettyShared) -> Self { PrettyTree::Shared(value) }
+}
+impl From<String> for PrettyTree<'_> {
+    fn from(value: String) -> Self { PrettyTree::String(Cow::Owned(value)) }
+}
+impl From<PathBuf> for PrettyTree<'_> {
+    fn from(value: PathBuf) -> Self { PrettyTree::String(Cow::Owned(value.to_string_lossy().into_owned())) }
+}
+impl From<()> for PrettyTree<'_> {
+    fn from(value: ()) -> Self { PrettyTree::leaf("()") }
+}
+impl From<bool> for PrettyTree<'_> {
+    fn from(value: bool) -> Self { PrettyTree::leaf(if value { "true" } else { "false" }) }
+}
@@ -0,0 +1,144 @@
+use std::time::Instant;
+
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::registry::LookupSpan;
+
+use crate::{Formatter, PrettyTree};
+
+/// A `tracing-subscriber` layer that prints each root span as a tree.
+///
+/// Spans and events become branches under the span they occurred in, with
+/// their fields as `key: value` children. Spans are annotated with the
+/// time between opening and closing (`[1.20ms]`), events with the time
+/// since their span opened (`[+0.31ms]`). The tree is printed when its
+/// root span closes; events outside of any span are printed on their own.
+pub struct PrettyTreeLayer {
+    formatter: Formatter,
+    writer: Box<dyn Fn(&str) + Send + Sync>,
+}
+
+impl Default for PrettyTreeLayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PrettyTreeLayer {
+    /// Prints to stderr with the default formatter.
+    pub fn new() -> Self {
+        Self { formatter: Formatter::default(), writer: Box::new(|x| eprintln!("{x}")) }
+    }
+    pub fn with_formatter(self, formatter: Formatter) -> Self {
+        Self { formatter, ..self }
+    }
+    /// Called with each rendered tree.
+    pub fn with_writer(self, writer: impl Fn(&str) + Send + Sync + 'static) -> Self {
+        Self { writer: Box::new(writer), ..self }
+    }
+    fn write(&self, tree: &PrettyTree) {
+        (self.writer)(&tree.format(&self.formatter))
+    }
+}
+
+/// The tree collected so far for an open span, kept in its extensions.
+struct SpanNode {
+    label: String,
+    opened: Instant,
+    fields: Vec<PrettyTree<'static>>,
+    children: Vec<PrettyTree<'static>>,
+    /// Index of this span's placeholder among its parent's children, so
+    /// spans keep their opening order rather than their closing order.
+    slot: Option<usize>,
+}
+
+/// Collects fields as `key: value` nodes, setting `message` aside.
+#[derive(Default)]
+struct FieldVisitor {
+    message: Option<String>,
+    fields: Vec<PrettyTree<'static>>,
+}
+
+impl Visit for FieldVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = Some(value.to_string());
+            return
+        }
+        let value = PrettyTree::String(value.to_string().into());
+        self.fields.push(PrettyTree::key_value(field.name(), value));
+    }
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        let value = format!("{value:?}");
+        if field.name() == "message" {
+            self.message = Some(value);
+            return
+        }
+        self.fields.push(PrettyTree::key_value(field.name(), PrettyTree::Value(value.into())));
+    }
+}
+
+impl<S> Layer<S> for PrettyTreeLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else { return };
+        let mut visitor = FieldVisitor::default();
+        attrs.record(&mut visitor);
+        let slot = span.parent().and_then(|parent| {
+            let mut extensions = parent.extensions_mut();
+            let node = extensions.get_mut::<SpanNode>()?;
+            node.children.push(PrettyTree::Empty);
+            Some(node.children.len() - 1)
+        });
+        let node = SpanNode {
+            label: span.name().to_string(),
+            opened: Instant::now(),
+            fields: visitor.fields,
+            children: Vec::default(),
+            slot,
+        };
+        span.extensions_mut().insert(node);
+    }
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else { return };
+        let mut visitor = FieldVisitor::default();
+        values.record(&mut visitor);
+        let mut extensions = span.extensions_mut();
+        if let Some(node) = extensions.get_mut::<SpanNode>() {
+            node.fields.extend(visitor.fields);
+        }
+    }
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let mut visitor = FieldVisitor::default();
+        event.record(&mut visitor);
+        let metadata = event.metadata();
+        let message = visitor.message.unwrap_or_else(|| metadata.name().to_string());
+        let label = format!("{} {message}", metadata.level());
+        let Some(span) = ctx.event_span(event) else {
+            return self.write(&PrettyTree::branch_of(label, visitor.fields))
+        };
+        let mut extensions = span.extensions_mut();
+        if let Some(node) = extensions.get_mut::<SpanNode>() {
+            let label = format!("{label} [+{:.2?}]", node.opened.elapsed());
+            node.children.push(PrettyTree::branch_of(label, visitor.fields));
+        }
+    }
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else { return };
+        let Some(node) = span.extensions_mut().remove::<SpanNode>() else { return };
+        let label = format!("{} [{:.2?}]", node.label, node.opened.elapsed());
+        let tree = PrettyTree::branch_of(label, node.fields.into_iter().chain(node.children));
+        let parent = span.parent();
+        let mut extensions = parent.as_ref().map(|x| x.extensions_mut());
+        let parent = extensions.as_mut().and_then(|x| x.get_mut::<SpanNode>());
+        match (parent, node.slot) {
+            (Some(parent), Some(slot)) => parent.children[slot] = tree,
+            (Some(parent), None) => parent.children.push(tree),
+            (None, _) => self.write(&tree),
+        }
+    }
+}
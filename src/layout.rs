@@ -0,0 +1,169 @@
+use unicode_width::UnicodeWidthStr;
+
+use crate::export::Outline;
+use crate::{FormatterStyle, GlyphStyle, Layout, PrettyTree};
+
+/// Renders `tree` with the top-down or left-to-right layout. The depth
+/// and breadth limits apply; the other formatter options only concern the
+/// indented layout, and the output is not colored.
+pub(crate) fn render(tree: &PrettyTree, style: &FormatterStyle) -> String {
+    let glyphs = Glyphs::new(style.glyphs);
+    let separator = match style.forest_separator {
+        Some(separator) => format!("\n{separator}\n"),
+        None => String::from("\n"),
+    };
+    Outline::roots(tree)
+        .iter()
+        .map(|root| {
+            let root = limit(root, style, 1);
+            let lines = match style.layout {
+                Layout::LeftToRight => left_to_right(&root, &glyphs),
+                _ => top_down(&root, &glyphs).lines,
+            };
+            lines.iter().map(|x| x.trim_end()).collect::<Vec<_>>().join("\n")
+        })
+        .collect::<Vec<_>>()
+        .join(&separator)
+}
+
+struct Glyphs {
+    horizontal: char,
+    vertical: char,
+    /// `┌`, `┐` and `└`.
+    corners: [char; 3],
+    /// `┬`, `┴`, `├`, `┤` and `┼`.
+    tees: [char; 5],
+}
+
+impl Glyphs {
+    fn new(style: GlyphStyle) -> Self {
+        match style {
+            GlyphStyle::Rounded => Self { horizontal: '─', vertical: '│', corners: ['╭', '╮', '╰'], tees: ['┬', '┴', '├', '┤', '┼'] },
+            GlyphStyle::Square => Self { horizontal: '─', vertical: '│', corners: ['┌', '┐', '└'], tees: ['┬', '┴', '├', '┤', '┼'] },
+            GlyphStyle::Ascii => Self { horizontal: '-', vertical: '|', corners: ['+', '+', '`'], tees: ['+'; 5] },
+        }
+    }
+}
+
+/// A copy of `node` cut off at the depth and breadth limits, marked with
+/// `…` leaves like in the indented layout.
+fn limit(node: &Outline, style: &FormatterStyle, depth: usize) -> Outline {
+    let leaf = |label: String| Outline { label, field: None, children: Vec::default() };
+    let mut children = match style.max_depth {
+        Some(max) if depth >= max && !node.children.is_empty() => vec![leaf(String::from("…"))],
+        _ => node.children.iter().map(|x| limit(x, style, depth + 1)).collect(),
+    };
+    if let Some(max) = style.max_children.filter(|max| children.len() > *max) {
+        let more = children.len() - max;
+        children.truncate(max);
+        children.push(leaf(format!("… {more} more")));
+    }
+    Outline { label: node.label.clone(), field: node.field.clone(), children }
+}
+
+/// A node's label on a single line.
+fn label(node: &Outline) -> String {
+    node.label.lines().collect::<Vec<_>>().join(" ")
+}
+
+fn pad(line: &str, width: usize) -> String {
+    format!("{line}{}", " ".repeat(width.saturating_sub(line.width())))
+}
+
+/// A rendered subtree: lines padded to the same width, and the column its
+/// root is centered on.
+struct Block {
+    lines: Vec<String>,
+    width: usize,
+    anchor: usize,
+}
+
+/// Spaces between the subtrees of siblings.
+const GAP: usize = 3;
+
+fn top_down(node: &Outline, glyphs: &Glyphs) -> Block {
+    let label = label(node);
+    let label_width = label.width();
+    let label_anchor = label_width.saturating_sub(1) / 2;
+    if node.children.is_empty() {
+        return Block { lines: vec![label], width: label_width, anchor: label_anchor }
+    }
+    let children = node.children.iter().map(|x| top_down(x, glyphs)).collect::<Vec<_>>();
+    let mut offsets = Vec::with_capacity(children.len());
+    let mut children_width = 0;
+    for child in children.iter() {
+        if !offsets.is_empty() {
+            children_width += GAP;
+        }
+        offsets.push(children_width);
+        children_width += child.width;
+    }
+    let anchors = children.iter().zip(offsets.iter()).map(|(x, offset)| offset + x.anchor).collect::<Vec<_>>();
+    let (first, last) = (anchors[0], anchors[anchors.len() - 1]);
+    // Center the label over the children, moving the children right if the
+    // label would stick out on the left.
+    let center = (first + last) / 2;
+    let shift = label_anchor.saturating_sub(center);
+    let anchor = center + shift;
+    let left = anchor - label_anchor;
+    let width = (children_width + shift).max(left + label_width);
+
+    let mut connector = vec![' '; width];
+    if anchors.len() == 1 {
+        connector[anchor] = glyphs.vertical;
+    } else {
+        let [down_right, down_left, _] = glyphs.corners;
+        let [tee_down, tee_up, tee_right, tee_left, cross] = glyphs.tees;
+        connector[first + shift..=last + shift].fill(glyphs.horizontal);
+        for x in anchors.iter() {
+            connector[x + shift] = tee_down;
+        }
+        connector[first + shift] = down_right;
+        connector[last + shift] = down_left;
+        connector[anchor] = match connector[anchor] {
+            x if x == down_right => tee_right,
+            x if x == down_left => tee_left,
+            x if x == tee_down => cross,
+            _ => tee_up,
+        };
+    }
+
+    let mut lines = vec![pad(&format!("{}{label}", " ".repeat(left)), width), connector.into_iter().collect()];
+    let height = children.iter().map(|x| x.lines.len()).max().unwrap_or_default();
+    for row in 0..height {
+        let cells = children
+            .iter()
+            .map(|x| x.lines.get(row).cloned().unwrap_or_else(|| " ".repeat(x.width)))
+            .collect::<Vec<_>>();
+        let line = format!("{}{}", " ".repeat(shift), cells.join(&" ".repeat(GAP)));
+        lines.push(pad(&line, width));
+    }
+    Block { lines, width, anchor }
+}
+
+fn left_to_right(node: &Outline, glyphs: &Glyphs) -> Vec<String> {
+    let label = label(node);
+    if node.children.is_empty() {
+        return vec![label]
+    }
+    let indent = " ".repeat(label.width());
+    let (h, v) = (glyphs.horizontal, glyphs.vertical);
+    let [_, _, up_right] = glyphs.corners;
+    let [tee_down, _, tee_right, _, _] = glyphs.tees;
+    let last_child = node.children.len() - 1;
+    let mut lines = Vec::default();
+    for (ix, child) in node.children.iter().enumerate() {
+        for (row, line) in left_to_right(child, glyphs).into_iter().enumerate() {
+            let prefix = match (ix, row) {
+                (0, 0) if last_child == 0 => format!("{label} {h}{h}{h} "),
+                (0, 0) => format!("{label} {h}{tee_down}{h} "),
+                (ix, 0) if ix == last_child => format!("{indent}  {up_right}{h} "),
+                (_, 0) => format!("{indent}  {tee_right}{h} "),
+                (ix, _) if ix == last_child => format!("{indent}     "),
+                _ => format!("{indent}  {v}  "),
+            };
+            lines.push(format!("{prefix}{line}"));
+        }
+    }
+    lines
+}
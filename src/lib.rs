@@ -2,34 +2,58 @@
 mod formatter;
 mod data;
 mod interface;
+mod stats;
+mod shared;
+mod source;
+mod type_name;
+mod debug;
+mod logging;
+mod export;
+mod select;
+mod ancestry;
+mod metric;
+mod outline;
+mod side_by_side;
+mod table;
+mod layout;
+pub mod fs;
+#[cfg(feature = "tracing-layer")]
+mod layer;
 
+use std::borrow::Cow;
 use std::fmt::Display;
 
 pub use formatter::*;
 pub use data::*;
 pub use interface::*;
+pub use stats::*;
+pub use shared::*;
+pub use source::*;
+pub use type_name::*;
+pub use debug::*;
+pub use logging::*;
+pub use side_by_side::*;
+pub use metric::*;
+#[cfg(feature = "tracing-layer")]
+pub use layer::*;
 
-pub fn branch_of<T: ToPrettyTree>(label: impl AsRef<str>, children: impl IntoIterator<Item=T>) -> PrettyTree {
-    let children = children
-        .into_iter()
-        .map(|x| x.to_pretty_tree())
-        .collect::<Vec<_>>();
+pub fn branch_of<'a, T: Into<PrettyTree<'a>>>(label: impl Into<Cow<'a, str>>, children: impl IntoIterator<Item=T>) -> PrettyTree<'a> {
     PrettyTree::branch_of(label, children)
 }
 
-pub fn field<T: ToPrettyTree>(key: impl AsRef<str>, value: &T) -> PrettyTree {
+pub fn field<'a, T: ToPrettyTree + ?Sized>(key: impl Into<Cow<'a, str>>, value: &'a T) -> PrettyTree<'a> {
     let value = value.to_pretty_tree();
-    PrettyTree::key_value(key.as_ref(), value)
+    PrettyTree::key_value(key, value)
 }
 
-pub fn string(value: impl AsRef<str>) -> PrettyTree {
-    PrettyTree::String(value.as_ref().to_string())
+pub fn string<'a>(value: impl Into<Cow<'a, str>>) -> PrettyTree<'a> {
+    PrettyTree::String(value.into())
 }
 
-pub fn value(value: impl AsRef<str>) -> PrettyTree {
-    PrettyTree::Value(value.as_ref().to_string())
+pub fn value<'a>(value: impl Into<Cow<'a, str>>) -> PrettyTree<'a> {
+    PrettyTree::Value(value.into())
 }
 
-pub fn branch_builder(label: impl AsRef<str>) -> PrettyBranch {
+pub fn branch_builder<'a>(label: impl Into<Cow<'a, str>>) -> PrettyBranch<'a> {
     PrettyBranch::new(label)
 }
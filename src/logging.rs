@@ -0,0 +1,54 @@
+use std::fmt::Display;
+
+use crate::{Formatter, FormatterStyle, PrettyTree};
+
+/// A tree rendered for log output.
+///
+/// Rendering happens in `Display::fmt`, so it only costs anything when the
+/// log record is actually written. The output never contains ANSI codes,
+/// whatever the terminal color detection says, since log sinks are often
+/// files.
+#[derive(Debug, Clone, Copy)]
+pub struct LogTree<'t, 'a>(pub &'t PrettyTree<'a>);
+
+impl Display for LogTree<'_, '_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let formatter = Formatter::new(FormatterStyle::default().use_color(false));
+        write!(f, "{}", self.0.format(&formatter))
+    }
+}
+
+impl<'a> PrettyTree<'a> {
+    pub fn log_display(&self) -> LogTree<'_, 'a> {
+        LogTree(self)
+    }
+}
+
+#[cfg(feature = "tracing")]
+impl<'a> PrettyTree<'a> {
+    /// A field value that renders the tree only if the event is enabled,
+    /// e.g. `tracing::info!(tree = tree.tracing_value(), "parsed")`.
+    /// Equivalent to `%tree.log_display()`.
+    pub fn tracing_value(&self) -> tracing::field::DisplayValue<LogTree<'_, 'a>> {
+        tracing::field::display(self.log_display())
+    }
+}
+
+#[cfg(feature = "log")]
+#[doc(hidden)]
+pub use log as __log;
+
+/// Logs `message` followed by the rendered tree on the next lines.
+///
+/// `log_tree!(log::Level::Debug, "parsed", tree)`. The tree is only
+/// rendered if the level is enabled.
+#[cfg(feature = "log")]
+#[macro_export]
+macro_rules! log_tree {
+    (target: $target:expr, $level:expr, $message:expr, $tree:expr) => {
+        $crate::__log::log!(target: $target, $level, "{}\n{}", $message, $crate::LogTree(&$tree))
+    };
+    ($level:expr, $message:expr, $tree:expr) => {
+        $crate::__log::log!($level, "{}\n{}", $message, $crate::LogTree(&$tree))
+    };
+}
@@ -0,0 +1,96 @@
+use crate::{PrettyBranch, PrettyFragment, PrettyList, PrettyTree};
+
+/// A node annotated with a number, such as a size, a duration or a count,
+/// which the formatter prints in a right-aligned column.
+#[derive(Debug, Clone)]
+pub struct PrettyMetric<'a> {
+    pub node: Box<PrettyTree<'a>>,
+    pub value: f64,
+}
+
+impl<'a> PrettyMetric<'a> {
+    pub fn new(node: impl Into<PrettyTree<'a>>, value: f64) -> Self {
+        Self { node: Box::new(node.into()), value }
+    }
+    pub fn into_owned(self) -> PrettyMetric<'static> {
+        PrettyMetric { node: Box::new(self.node.into_owned()), value: self.value }
+    }
+}
+
+/// A metric as printed: whole numbers without decimals, others with two.
+pub(crate) fn metric_text(value: f64) -> String {
+    match value.fract() == 0.0 {
+        true => format!("{value:.0}"),
+        false => format!("{value:.2}"),
+    }
+}
+
+impl PartialEq for PrettyMetric<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.node == other.node && self.value.to_bits() == other.value.to_bits()
+    }
+}
+impl Eq for PrettyMetric<'_> {}
+
+impl std::hash::Hash for PrettyMetric<'_> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.node.hash(state);
+        self.value.to_bits().hash(state);
+    }
+}
+
+impl<'a> PrettyTree<'a> {
+    /// Annotates the node with a metric.
+    pub fn with_metric(self, value: f64) -> Self {
+        PrettyTree::Metric(PrettyMetric::new(self, value))
+    }
+    /// Adds the metrics of each node's descendants to its own, like `du`
+    /// does for directory sizes. Nodes without a metric of their own get
+    /// the sum of their children's, if any of them has one. Shared nodes
+    /// are left as they are.
+    pub fn sum_metrics(self) -> Self {
+        sum(self).0
+    }
+}
+
+/// The tree with metrics summed, and its total.
+fn sum<'a>(tree: PrettyTree<'a>) -> (PrettyTree<'a>, Option<f64>) {
+    match tree {
+        PrettyTree::Metric(x) => {
+            let (node, below) = sum_children(*x.node);
+            let value = x.value + below.unwrap_or_default();
+            (node.with_metric(value), Some(value))
+        }
+        tree @ PrettyTree::Fragment(_) => sum_children(tree),
+        tree => match sum_children(tree) {
+            (tree, Some(value)) => (tree.with_metric(value), Some(value)),
+            (tree, None) => (tree, None),
+        },
+    }
+}
+
+/// The tree with the metrics of its children summed, and their total.
+fn sum_children<'a>(tree: PrettyTree<'a>) -> (PrettyTree<'a>, Option<f64>) {
+    let mut total = None;
+    let mut sum_all = |nodes: Vec<PrettyTree<'a>>| {
+        nodes
+            .into_iter()
+            .map(|x| {
+                let (node, value) = sum(x);
+                if let Some(value) = value {
+                    *total.get_or_insert(0.0) += value;
+                }
+                node
+            })
+            .collect::<Vec<_>>()
+    };
+    let tree = match tree {
+        PrettyTree::Branch(x) => {
+            PrettyTree::Branch(PrettyBranch { label: x.label, children: sum_all(x.children) })
+        }
+        PrettyTree::Fragment(x) => PrettyTree::Fragment(PrettyFragment { nodes: sum_all(x.nodes) }),
+        PrettyTree::List(x) => PrettyTree::List(PrettyList { nodes: sum_all(x.nodes), ..x }),
+        tree => tree,
+    };
+    (tree, total)
+}
@@ -0,0 +1,94 @@
+use crate::{PrettyBranch, PrettyTree};
+
+impl<'a> PrettyTree<'a> {
+    /// Parses lines nested by indentation, one node per non-blank line.
+    /// Tabs count as four spaces. Several top-level lines give a fragment.
+    pub fn from_indented(text: &'a str) -> Self {
+        let lines = text
+            .lines()
+            .filter(|x| !x.trim().is_empty())
+            .map(|line| (indentation(line), line.trim()));
+        nest(lines)
+    }
+    /// Parses the heading and list structure of a Markdown document.
+    ///
+    /// Headings nest by level, and list items nest under the preceding
+    /// heading and by indentation. Other text and fenced code blocks are
+    /// skipped.
+    pub fn from_markdown_outline(text: &'a str) -> Self {
+        let mut in_code_block = false;
+        let lines = text.lines().filter_map(move |line| {
+            let trimmed = line.trim();
+            if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+                in_code_block = !in_code_block;
+                return None
+            }
+            if in_code_block {
+                return None
+            }
+            if let Some(heading) = heading(trimmed) {
+                return Some(heading)
+            }
+            // List items rank below every heading level.
+            let item = list_item(trimmed)?;
+            Some((7 + indentation(line), item))
+        });
+        nest(lines)
+    }
+}
+
+fn indentation(line: &str) -> usize {
+    line.chars()
+        .take_while(|x| x.is_whitespace())
+        .map(|x| if x == '\t' { 4 } else { 1 })
+        .sum()
+}
+
+/// `(level, text)` for `#` to `######` headings.
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|x| *x == '#').count();
+    let text = line[level..].strip_prefix(' ')?;
+    let text = text.trim_end_matches('#').trim();
+    (1..=6).contains(&level).then_some((level, text))
+}
+
+/// The text of a `-`, `*`, `+` or `1.` list item.
+fn list_item(line: &str) -> Option<&str> {
+    if let Some(text) = line.strip_prefix(['-', '*', '+']) {
+        return text.strip_prefix(' ').map(str::trim)
+    }
+    let digits = line.chars().take_while(char::is_ascii_digit).count();
+    if digits == 0 {
+        return None
+    }
+    let text = line[digits..].strip_prefix(['.', ')'])?;
+    text.strip_prefix(' ').map(str::trim)
+}
+
+/// Builds branches from `(depth, label)` pairs, where each line is a child
+/// of the closest preceding line with a smaller depth.
+fn nest<'a>(lines: impl IntoIterator<Item = (usize, &'a str)>) -> PrettyTree<'a> {
+    fn close<'a>(stack: &mut Vec<(usize, PrettyBranch<'a>)>, roots: &mut Vec<PrettyTree<'a>>) {
+        let (_, branch) = stack.pop().unwrap();
+        match stack.last_mut() {
+            Some((_, parent)) => parent.children.push(branch.into()),
+            None => roots.push(branch.into()),
+        }
+    }
+    let mut stack = Vec::<(usize, PrettyBranch<'a>)>::default();
+    let mut roots = Vec::default();
+    for (depth, label) in lines {
+        while stack.last().is_some_and(|(x, _)| *x >= depth) {
+            close(&mut stack, &mut roots);
+        }
+        stack.push((depth, PrettyBranch::new(label)));
+    }
+    while !stack.is_empty() {
+        close(&mut stack, &mut roots);
+    }
+    match roots.len() {
+        0 => PrettyTree::Empty,
+        1 => roots.pop().unwrap(),
+        _ => PrettyTree::fragment(roots),
+    }
+}
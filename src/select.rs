@@ -0,0 +1,67 @@
+use crate::source::{node_children, node_label};
+use crate::PrettyTree;
+
+impl<'a> PrettyTree<'a> {
+    /// The descendants matching a dot-separated selector such as
+    /// `users.*.name`, starting below the root.
+    ///
+    /// A segment matches children whose label equals it, or fields with
+    /// that key. `*` matches every child, and a number `n`
+    /// picks the `n`th child when no label matches. An empty selector
+    /// selects the root itself.
+    pub fn select(&self, selector: &str) -> Vec<PrettyTree<'a>> {
+        let mut selected = vec![self.clone()];
+        for segment in selector.split('.').filter(|x| !x.is_empty()) {
+            selected = selected
+                .iter()
+                .flat_map(|node| select_children(node, segment))
+                .collect();
+        }
+        selected
+    }
+    /// The node at a dot-separated path of child indices, such as `0.3.1`
+    /// from a [`Gutter::Path`](crate::Gutter::Path) gutter. The first index
+    /// picks a tree of a top-level fragment, and is `0` for any other tree.
+    pub fn get_by_path(&self, path: &str) -> Option<PrettyTree<'a>> {
+        let mut indices = path.split('.').map(|x| x.trim().parse::<usize>().ok());
+        let root = resolve(self);
+        let mut node = match root {
+            PrettyTree::Fragment(_) => node_children(&root).into_iter().nth(indices.next()??)?,
+            root => Some(root).filter(|_| indices.next() == Some(Some(0)))?,
+        };
+        for index in indices {
+            node = node_children(&resolve(&node)).into_iter().nth(index?)?;
+        }
+        Some(resolve(&node))
+    }
+}
+
+/// What a shared node points to, or the node itself.
+fn resolve<'a>(node: &PrettyTree<'a>) -> PrettyTree<'a> {
+    match node {
+        PrettyTree::Shared(x) => resolve(&x.lock()),
+        node => node.clone(),
+    }
+}
+
+fn select_children<'a>(node: &PrettyTree<'a>, segment: &str) -> Vec<PrettyTree<'a>> {
+    let children = node_children(node);
+    if segment == "*" {
+        return children
+    }
+    let matching = children
+        .iter()
+        .filter(|x| match x {
+            PrettyTree::Field(field) => field.key == segment,
+            x => node_label(x) == segment,
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+    if !matching.is_empty() {
+        return matching
+    }
+    match segment.parse::<usize>() {
+        Ok(index) => children.into_iter().nth(index).into_iter().collect(),
+        Err(_) => Vec::default(),
+    }
+}
@@ -0,0 +1,106 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use crate::{PrettyTree, ToPrettyTree};
+
+/// A node that may be referenced from several parents, including itself.
+///
+/// Cloning a `PrettyShared` clones the handle, not the subtree. The
+/// formatter prints a shared node in full at its first occurrence, marked
+/// with `#N`, and renders every later occurrence as `↑ ref #N`, which also
+/// breaks cycles. Like any `Arc` cycle, a cyclic tree is never freed.
+///
+/// Shared nodes own their contents; borrowed text is copied on insertion.
+#[derive(Clone, Default)]
+pub struct PrettyShared(Arc<Mutex<PrettyTree<'static>>>);
+
+impl PrettyShared {
+    pub fn new<'a>(tree: impl Into<PrettyTree<'a>>) -> Self {
+        Self(Arc::new(Mutex::new(tree.into().into_owned())))
+    }
+    /// Replaces the node's contents, e.g. to close a cycle after the node
+    /// has been referenced.
+    pub fn set<'a>(&self, tree: impl Into<PrettyTree<'a>>) {
+        let tree = tree.into().into_owned();
+        *self.lock() = tree;
+    }
+    pub(crate) fn lock(&self) -> MutexGuard<'_, PrettyTree<'static>> {
+        self.0.lock().unwrap_or_else(|error| error.into_inner())
+    }
+    /// Identity of the node; equal for all handles of the same node.
+    pub(crate) fn id(&self) -> usize {
+        Arc::as_ptr(&self.0) as usize
+    }
+}
+
+impl PartialEq for PrettyShared {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+impl Eq for PrettyShared {}
+
+impl std::hash::Hash for PrettyShared {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.id().hash(state)
+    }
+}
+
+impl std::fmt::Debug for PrettyShared {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "PrettyShared({:#x})", self.id())
+    }
+}
+
+impl ToPrettyTree for PrettyShared {
+    fn to_pretty_tree(&self) -> PrettyTree<'_> {
+        PrettyTree::Shared(self.clone())
+    }
+}
+
+/// Builds trees with shared nodes from a graph keyed by node ids.
+#[derive(Debug, Clone, Default)]
+pub struct SharedNodes {
+    nodes: HashMap<usize, PrettyShared>,
+}
+
+impl SharedNodes {
+    /// Returns a reference to the node with the given id, building it with
+    /// `build` the first time the id is seen.
+    ///
+    /// The node is registered before `build` runs, so a node that
+    /// (indirectly) refers to itself becomes a cycle instead of recursing
+    /// forever.
+    pub fn get_or_insert_with<'a>(
+        &mut self,
+        id: usize,
+        build: impl FnOnce(&mut Self) -> PrettyTree<'a>,
+    ) -> PrettyTree<'static> {
+        if let Some(node) = self.nodes.get(&id) {
+            return PrettyTree::Shared(node.clone())
+        }
+        let node = PrettyShared::default();
+        self.nodes.insert(id, node.clone());
+        node.set(build(self));
+        PrettyTree::Shared(node)
+    }
+    /// Like [`SharedNodes::get_or_insert_with`], using the address of the
+    /// `Rc` allocation as the id.
+    pub fn get_or_insert_rc<'a, T>(
+        &mut self,
+        node: &Rc<T>,
+        build: impl FnOnce(&mut Self) -> PrettyTree<'a>,
+    ) -> PrettyTree<'static> {
+        self.get_or_insert_with(Rc::as_ptr(node) as *const () as usize, build)
+    }
+    pub fn get(&self, id: usize) -> Option<PrettyTree<'static>> {
+        self.nodes.get(&id).cloned().map(PrettyTree::Shared)
+    }
+}
+
+impl PrettyTree<'_> {
+    pub fn shared(node: &PrettyShared) -> Self {
+        Self::Shared(node.clone())
+    }
+}
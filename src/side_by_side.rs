@@ -0,0 +1,186 @@
+use colored::Colorize;
+use unicode_width::UnicodeWidthStr;
+
+use crate::formatter::NodeLine;
+use crate::{Formatter, GlyphStyle, PrettyTree};
+
+/// Renders `left` and `right` in two columns, with rows aligned by
+/// structure.
+///
+/// Children are matched by their text, like lines in a diff: nodes only
+/// present on one side get a blank row on the other, and unmatched nodes
+/// at the same position are shown next to each other, with their children
+/// aligned in turn. With
+/// [`FormatterStyle::highlight_changes`](crate::FormatterStyle::highlight_changes)
+/// the separator of differing rows is `≠` instead of `│`.
+pub fn side_by_side(left: &PrettyTree, right: &PrettyTree, formatter: &Formatter) -> String {
+    let left = Column::new(left, formatter);
+    let right = Column::new(right, formatter);
+    let mut rows = Vec::default();
+    let pairs = align(&left, &left.roots, &right, &right.roots);
+    emit(&left, &right, pairs, &mut rows);
+
+    let style = formatter.style();
+    let width = left.lines.iter().map(|x| display_width(x)).max().unwrap_or_default();
+    let (same, changed) = match style.glyphs {
+        GlyphStyle::Ascii => ("|", "!"),
+        _ => ("│", "≠"),
+    };
+    rows.into_iter()
+        .map(|row| {
+            let left_line = row.left.unwrap_or_default();
+            let padding = " ".repeat(width - display_width(left_line));
+            let separator = match row.changed && style.highlight_changes {
+                true if style.use_color => changed.red().to_string(),
+                true => changed.to_string(),
+                false => same.to_string(),
+            };
+            let right_line = row.right.unwrap_or_default();
+            format!("{left_line}{padding} {separator} {right_line}").trim_end().to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// One side: its output lines and the nodes they belong to.
+struct Column {
+    lines: Vec<String>,
+    nodes: Vec<NodeLine>,
+    /// Index of each node's first line.
+    offsets: Vec<usize>,
+    /// Children of each node, by index.
+    children: Vec<Vec<usize>>,
+    roots: Vec<usize>,
+}
+
+impl Column {
+    fn new(tree: &PrettyTree, formatter: &Formatter) -> Self {
+        let (output, nodes) = formatter.format_lines(tree);
+        let lines = output.lines().map(str::to_string).collect();
+        let offsets = nodes
+            .iter()
+            .scan(0, |offset, node| {
+                let start = *offset;
+                *offset += node.line_count;
+                Some(start)
+            })
+            .collect();
+        // Each node is a child of the closest preceding node with a
+        // smaller depth.
+        let mut children = vec![Vec::default(); nodes.len()];
+        let mut roots = Vec::default();
+        let mut stack = Vec::<usize>::default();
+        for (ix, node) in nodes.iter().enumerate() {
+            while stack.last().is_some_and(|x| nodes[*x].depth >= node.depth) {
+                stack.pop();
+            }
+            match stack.last() {
+                Some(parent) => children[*parent].push(ix),
+                None => roots.push(ix),
+            }
+            stack.push(ix);
+        }
+        Self { lines, nodes, offsets, children, roots }
+    }
+    fn node_lines(&self, node: usize) -> &[String] {
+        let start = self.offsets[node].min(self.lines.len());
+        let end = (start + self.nodes[node].line_count).min(self.lines.len());
+        &self.lines[start..end]
+    }
+}
+
+enum Pair {
+    Both(usize, usize),
+    Left(usize),
+    Right(usize),
+}
+
+struct Row<'a> {
+    left: Option<&'a str>,
+    right: Option<&'a str>,
+    changed: bool,
+}
+
+/// Matches two lists of siblings by the longest common subsequence of
+/// their text. Unmatched nodes between two matches are paired up by
+/// position.
+fn align(left: &Column, left_nodes: &[usize], right: &Column, right_nodes: &[usize]) -> Vec<Pair> {
+    let text = |column: &Column, node: usize| column.nodes[node].text.clone();
+    let (n, m) = (left_nodes.len(), right_nodes.len());
+    let mut lengths = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lengths[i][j] = match text(left, left_nodes[i]) == text(right, right_nodes[j]) {
+                true => lengths[i + 1][j + 1] + 1,
+                false => lengths[i + 1][j].max(lengths[i][j + 1]),
+            };
+        }
+    }
+    let mut pairs = Vec::default();
+    let (mut removed, mut added) = (Vec::default(), Vec::default());
+    let flush = |pairs: &mut Vec<Pair>, removed: &mut Vec<usize>, added: &mut Vec<usize>| {
+        let paired = removed.len().min(added.len());
+        pairs.extend(removed.iter().zip(added.iter()).map(|(l, r)| Pair::Both(*l, *r)));
+        pairs.extend(removed.drain(..).skip(paired).map(Pair::Left));
+        pairs.extend(added.drain(..).skip(paired).map(Pair::Right));
+    };
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && text(left, left_nodes[i]) == text(right, right_nodes[j]) {
+            flush(&mut pairs, &mut removed, &mut added);
+            pairs.push(Pair::Both(left_nodes[i], right_nodes[j]));
+            i += 1;
+            j += 1;
+        } else if j == m || (i < n && lengths[i + 1][j] >= lengths[i][j + 1]) {
+            removed.push(left_nodes[i]);
+            i += 1;
+        } else {
+            added.push(right_nodes[j]);
+            j += 1;
+        }
+    }
+    flush(&mut pairs, &mut removed, &mut added);
+    pairs
+}
+
+fn emit<'a>(left: &'a Column, right: &'a Column, pairs: Vec<Pair>, rows: &mut Vec<Row<'a>>) {
+    for pair in pairs {
+        match pair {
+            Pair::Both(l, r) => {
+                let changed = left.nodes[l].text != right.nodes[r].text;
+                let (left_lines, right_lines) = (left.node_lines(l), right.node_lines(r));
+                for ix in 0..left_lines.len().max(right_lines.len()) {
+                    let left = left_lines.get(ix).map(String::as_str);
+                    let right = right_lines.get(ix).map(String::as_str);
+                    rows.push(Row { left, right, changed });
+                }
+                let children = align(left, &left.children[l], right, &right.children[r]);
+                emit(left, right, children, rows);
+            }
+            Pair::Left(l) => {
+                rows.extend(left.node_lines(l).iter().map(|x| Row { left: Some(x), right: None, changed: true }));
+                let children = left.children[l].iter().copied().map(Pair::Left).collect();
+                emit(left, right, children, rows);
+            }
+            Pair::Right(r) => {
+                rows.extend(right.node_lines(r).iter().map(|x| Row { left: None, right: Some(x), changed: true }));
+                let children = right.children[r].iter().copied().map(Pair::Right).collect();
+                emit(left, right, children, rows);
+            }
+        }
+    }
+}
+
+/// Terminal columns taken by `line`, ignoring ANSI color codes.
+pub(crate) fn display_width(line: &str) -> usize {
+    let mut plain = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(char) = chars.next() {
+        if char == '\x1b' {
+            chars.by_ref().find(|x| x.is_ascii_alphabetic());
+            continue
+        }
+        plain.push(char);
+    }
+    plain.width()
+}
@@ -0,0 +1,181 @@
+use crate::{short_type_name, Formatter, PrettyTree, ToPrettyTree};
+
+/// A tree that is materialized on demand.
+///
+/// Unlike [`ToPrettyTree`], which builds the whole tree up front, a source
+/// only produces the nodes the formatter actually pulls, so rendering the
+/// first few levels of a huge structure only touches those levels. Every
+/// [`ToPrettyTree`] implementor is also a source.
+pub trait PrettyTreeSource {
+    /// The text printed on this node's line.
+    fn label(&self) -> String;
+    /// The node's children, produced lazily.
+    fn children(&self) -> Box<dyn Iterator<Item = Box<dyn PrettyTreeSource + '_>> + '_>;
+    /// The label and children at once, which is what the formatter pulls.
+    /// Sources that compute both from the same data can override this to
+    /// do it once.
+    fn expand(&self) -> SourceNode<'_> {
+        SourceNode { label: self.label(), id: None, children: self.children() }
+    }
+}
+
+/// A source node's label and children, see [`PrettyTreeSource::expand`].
+pub struct SourceNode<'s> {
+    pub label: String,
+    /// Identifies a node that can be reached along several paths, such as
+    /// a shared node, so that cycles through it can be cut.
+    pub id: Option<usize>,
+    pub children: Box<dyn Iterator<Item = Box<dyn PrettyTreeSource + 's>> + 's>,
+}
+
+impl<T: ToPrettyTree> PrettyTreeSource for T {
+    fn label(&self) -> String {
+        node_label(&self.to_pretty_tree())
+    }
+    fn children(&self) -> Box<dyn Iterator<Item = Box<dyn PrettyTreeSource + '_>> + '_> {
+        owned_children(&self.to_pretty_tree())
+    }
+    fn expand(&self) -> SourceNode<'_> {
+        expand(&self.to_pretty_tree())
+    }
+}
+
+/// An already materialized node, so its children are not re-converted.
+struct OwnedNode<'a>(PrettyTree<'a>);
+
+impl PrettyTreeSource for OwnedNode<'_> {
+    fn label(&self) -> String {
+        node_label(&self.0)
+    }
+    fn children(&self) -> Box<dyn Iterator<Item = Box<dyn PrettyTreeSource + '_>> + '_> {
+        owned_children(&self.0)
+    }
+    fn expand(&self) -> SourceNode<'_> {
+        expand(&self.0)
+    }
+}
+
+fn expand<'a>(tree: &PrettyTree<'a>) -> SourceNode<'a> {
+    let id = match tree {
+        PrettyTree::Shared(x) => Some(x.id()),
+        _ => None,
+    };
+    SourceNode { label: node_label(tree), id, children: owned_children(tree) }
+}
+
+fn owned_children<'a>(tree: &PrettyTree<'a>) -> Box<dyn Iterator<Item = Box<dyn PrettyTreeSource + 'a>> + 'a> {
+    let children = node_children(tree);
+    Box::new(children.into_iter().map(|x| Box::new(OwnedNode(x)) as Box<dyn PrettyTreeSource>))
+}
+
+pub(crate) fn node_label(tree: &PrettyTree) -> String {
+    match tree {
+        PrettyTree::Empty => String::default(),
+        PrettyTree::Value(x) => x.to_string(),
+        PrettyTree::String(x) => format!("{x:?}"),
+        PrettyTree::Field(x) => x.to_string(),
+        PrettyTree::Branch(x) => x.label.to_string(),
+        PrettyTree::Fragment(_) => String::default(),
+        PrettyTree::Shared(x) => node_label(&x.lock()),
+        PrettyTree::List(x) => x.data_type.as_deref().map(short_type_name).unwrap_or_default(),
+        PrettyTree::Metric(x) => node_label(&x.node),
+    }
+}
+
+/// Children with nested fragments flattened and unordered lists sorted.
+/// Shared and metric nodes have the children of the node they wrap.
+pub(crate) fn node_children<'a>(tree: &PrettyTree<'a>) -> Vec<PrettyTree<'a>> {
+    fn flatten<'a>(nodes: &[PrettyTree<'a>], out: &mut Vec<PrettyTree<'a>>) {
+        for node in nodes {
+            match node {
+                PrettyTree::Empty => (),
+                PrettyTree::Fragment(x) => flatten(&x.nodes, out),
+                x => out.push(x.clone()),
+            }
+        }
+    }
+    let mut children = Vec::default();
+    match tree {
+        PrettyTree::Branch(x) => flatten(&x.children, &mut children),
+        PrettyTree::Fragment(x) => flatten(&x.nodes, &mut children),
+        PrettyTree::List(x) => flatten(&x.sorted_nodes(), &mut children),
+        PrettyTree::Shared(x) => return node_children(&x.lock()),
+        PrettyTree::Metric(x) => return node_children(&x.node),
+        _ => (),
+    }
+    children
+}
+
+impl PrettyTree<'static> {
+    /// Pulls at most `max_depth` levels and `max_children` children per node
+    /// out of `source`. Cut off parts are marked with a `…` leaf, and a node
+    /// that contains itself with a `↑ ref` leaf.
+    pub fn from_source(
+        source: &dyn PrettyTreeSource,
+        max_depth: Option<usize>,
+        max_children: Option<usize>,
+    ) -> Self {
+        Self::pull(source, max_depth, max_children, &mut Vec::default())
+    }
+    /// Like [`from_source`](Self::from_source), with the ids of the nodes
+    /// on the current path in `path`.
+    fn pull(
+        source: &dyn PrettyTreeSource,
+        max_depth: Option<usize>,
+        max_children: Option<usize>,
+        path: &mut Vec<usize>,
+    ) -> Self {
+        let SourceNode { label, id, children } = source.expand();
+        if let Some(id) = id {
+            if path.contains(&id) {
+                return PrettyTree::value("↑ ref")
+            }
+            path.push(id);
+        }
+        let tree = Self::pull_children(label, children, max_depth, max_children, path);
+        if id.is_some() {
+            path.pop();
+        }
+        tree
+    }
+    fn pull_children<'s>(
+        label: String,
+        mut children: Box<dyn Iterator<Item = Box<dyn PrettyTreeSource + 's>> + 's>,
+        max_depth: Option<usize>,
+        max_children: Option<usize>,
+        path: &mut Vec<usize>,
+    ) -> Self {
+        if max_depth.is_some_and(|x| x <= 1) {
+            if children.next().is_some() {
+                return PrettyTree::branch_of(label, [PrettyTree::value("…")])
+            }
+            return PrettyTree::Value(label.into())
+        }
+        let max_depth = max_depth.map(|x| x - 1);
+        let mut nodes = children
+            .by_ref()
+            .take(max_children.unwrap_or(usize::MAX))
+            .map(|x| PrettyTree::pull(x.as_ref(), max_depth, max_children, path))
+            .collect::<Vec<_>>();
+        if children.next().is_some() {
+            nodes.push(PrettyTree::value("…"));
+        }
+        if nodes.is_empty() {
+            return PrettyTree::Value(label.into())
+        }
+        PrettyTree::branch_of(label, nodes)
+    }
+}
+
+impl Formatter {
+    /// Renders a lazy source, pulling only as far as the formatter's depth
+    /// and breadth limits allow.
+    pub fn format_source(&self, source: &dyn PrettyTreeSource) -> String {
+        let style = self.style();
+        let tree = PrettyTree::from_source(source, style.max_depth, style.max_children);
+        let formatter = self
+            .clone()
+            .map_formatter_style(|x| x.max_depth(None).max_children(None));
+        tree.format(&formatter)
+    }
+}
@@ -0,0 +1,152 @@
+use std::collections::{BTreeMap, HashSet};
+
+use crate::{short_type_name, PrettyBranch, PrettyTree, ToPrettyTree};
+
+/// Aggregate information about a tree, gathered without rendering it.
+///
+/// Fragments are transparent: their nodes are counted as if they were
+/// direct children of the enclosing branch. `Empty` nodes are not counted.
+#[derive(Debug, Clone, Default)]
+pub struct TreeStats {
+    /// Total number of branch and leaf nodes.
+    pub node_count: usize,
+    /// Number of nodes without children.
+    pub leaf_count: usize,
+    /// Number of nodes on the longest root-to-leaf path.
+    pub max_depth: usize,
+    /// Largest number of children under a single branch.
+    pub max_fan_out: usize,
+    /// How often each label occurs.
+    pub labels: BTreeMap<String, usize>,
+    /// Labels along the longest root-to-leaf path.
+    pub deepest_path: Vec<String>,
+}
+
+impl TreeStats {
+    pub fn new(tree: &PrettyTree) -> Self {
+        let mut stats = Self::default();
+        let mut path = Vec::default();
+        let mut seen = HashSet::default();
+        stats.visit(tree, &mut path, &mut seen);
+        stats
+    }
+    /// Labels ordered by descending frequency, ties broken alphabetically.
+    pub fn most_common_labels(&self, limit: usize) -> Vec<(&str, usize)> {
+        let mut labels = self.labels
+            .iter()
+            .map(|(label, count)| (label.as_str(), *count))
+            .collect::<Vec<_>>();
+        labels.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        labels.truncate(limit);
+        labels
+    }
+    /// A tree describing the totals, the `top` most common labels and the
+    /// deepest path.
+    pub fn summary(&self, top: usize) -> PrettyTree<'_> {
+        let labels = self
+            .most_common_labels(top)
+            .into_iter()
+            .map(|(label, count)| PrettyTree::key_value(label, count))
+            .collect::<Vec<_>>();
+        let deepest_path = self.deepest_path
+            .iter()
+            .map(PrettyTree::leaf)
+            .collect::<Vec<_>>();
+        PrettyBranch::new("summary")
+            .with_field("nodes", self.node_count)
+            .with_field("leaves", self.leaf_count)
+            .with_field("max depth", self.max_depth)
+            .with_field("max fan-out", self.max_fan_out)
+            .with_child(PrettyTree::branch_of(format!("top {top} labels"), labels))
+            .with_child(PrettyTree::branch_of("deepest path", deepest_path))
+            .into()
+    }
+    /// Shared nodes are visited once, at their first occurrence.
+    fn visit(&mut self, tree: &PrettyTree, path: &mut Vec<String>, seen: &mut HashSet<usize>) {
+        match tree {
+            PrettyTree::Empty => (),
+            PrettyTree::Value(x) => self.leaf(x.to_string(), path),
+            PrettyTree::String(x) => self.leaf(format!("{x:?}"), path),
+            PrettyTree::Field(x) => self.leaf(x.to_string(), path),
+            PrettyTree::Branch(branch) => {
+                self.branch(branch.label.to_string(), &branch.children, path, seen)
+            }
+            PrettyTree::Fragment(fragment) => {
+                for node in fragment.nodes.iter() {
+                    self.visit(node, path, seen);
+                }
+            }
+            PrettyTree::List(list) => {
+                let label = list.data_type.as_deref().map(short_type_name).unwrap_or_default();
+                self.branch(label, &list.sorted_nodes(), path, seen)
+            }
+            PrettyTree::Shared(node) => {
+                if seen.insert(node.id()) {
+                    self.visit(&node.lock(), path, seen);
+                }
+            }
+            PrettyTree::Metric(x) => self.visit(&x.node, path, seen),
+        }
+    }
+    fn branch(
+        &mut self,
+        label: String,
+        children: &[PrettyTree],
+        path: &mut Vec<String>,
+        seen: &mut HashSet<usize>,
+    ) {
+        let fan_out = count_children(children);
+        if fan_out == 0 {
+            return self.leaf(label, path)
+        }
+        self.node(label.clone());
+        self.max_fan_out = self.max_fan_out.max(fan_out);
+        path.push(label);
+        for child in children.iter() {
+            self.visit(child, path, seen);
+        }
+        path.pop();
+    }
+    fn node(&mut self, label: String) {
+        self.node_count += 1;
+        *self.labels.entry(label).or_default() += 1;
+    }
+    fn leaf(&mut self, label: String, path: &[String]) {
+        let depth = path.len() + 1;
+        if depth > self.max_depth {
+            self.max_depth = depth;
+            self.deepest_path = path.to_vec();
+            self.deepest_path.push(label.clone());
+        }
+        self.leaf_count += 1;
+        self.node(label);
+    }
+}
+
+impl ToPrettyTree for TreeStats {
+    fn to_pretty_tree(&self) -> PrettyTree<'_> {
+        self.summary(self.labels.len())
+    }
+}
+
+/// Number of children once fragments are flattened.
+fn count_children(children: &[PrettyTree]) -> usize {
+    children
+        .iter()
+        .map(|child| match child {
+            PrettyTree::Empty => 0,
+            PrettyTree::Fragment(fragment) => count_children(&fragment.nodes),
+            _ => 1,
+        })
+        .sum()
+}
+
+impl PrettyTree<'_> {
+    pub fn stats(&self) -> TreeStats {
+        TreeStats::new(self)
+    }
+    /// Renders [`TreeStats::summary`] instead of the tree itself.
+    pub fn render_summary(&self, top: usize) -> String {
+        self.stats().summary(top).render()
+    }
+}
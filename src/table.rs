@@ -0,0 +1,74 @@
+use std::borrow::Cow;
+
+use unicode_width::UnicodeWidthStr;
+
+use crate::PrettyTree;
+
+/// Rewrites records into aligned table rows: a header row with the field
+/// names, then one row per record.
+///
+/// Records are branches whose children are all fields with
+/// the same keys in the same order. Returns `None` unless there are at
+/// least two records and every child is one. The branch labels become a
+/// leading column unless they are all the same, such as a struct name.
+pub(crate) fn tabulate(children: &[PrettyTree], separator: &str) -> Option<Vec<PrettyTree<'static>>> {
+    if children.len() < 2 {
+        return None
+    }
+    let records = children.iter().map(record).collect::<Option<Vec<_>>>()?;
+    let (first_label, first_fields) = records.first()?;
+    let keys = first_fields.iter().map(|(key, _)| *key).collect::<Vec<_>>();
+    let uniform = records.iter().all(|(_, fields)| {
+        fields.len() == keys.len() && fields.iter().zip(keys.iter()).all(|((key, _), expected)| key == expected)
+    });
+    if !uniform {
+        return None
+    }
+    let labeled = records.iter().any(|(label, _)| label != first_label);
+    let mut rows = Vec::with_capacity(records.len() + 1);
+    let header = labeled.then_some("").into_iter().chain(keys.iter().copied()).collect::<Vec<_>>();
+    rows.push(header);
+    for (label, fields) in records.iter() {
+        let row = labeled.then_some(*label).into_iter().chain(fields.iter().map(|(_, value)| value.as_ref()));
+        rows.push(row.collect());
+    }
+    let mut widths = vec![0; rows[0].len()];
+    for row in rows.iter() {
+        for (width, cell) in widths.iter_mut().zip(row.iter()) {
+            *width = (*width).max(cell.width());
+        }
+    }
+    let rows = rows
+        .into_iter()
+        .map(|row| {
+            let cells = row
+                .iter()
+                .zip(widths.iter())
+                .map(|(cell, width)| format!("{cell}{}", " ".repeat(width - cell.width())))
+                .collect::<Vec<_>>();
+            let line = cells.join(&format!(" {separator} "));
+            PrettyTree::Value(line.trim_end().to_string().into())
+        })
+        .collect();
+    Some(rows)
+}
+
+/// A branch's label and its fields as `(key, printed value)` pairs.
+type Record<'t> = (&'t str, Vec<(&'t str, Cow<'t, str>)>);
+
+/// A branch's label and `(key, value)` fields, if all its children are
+/// single-line fields.
+fn record<'t>(tree: &'t PrettyTree) -> Option<Record<'t>> {
+    let PrettyTree::Branch(branch) = tree else { return None };
+    if branch.children.is_empty() {
+        return None
+    }
+    let fields = branch.children
+        .iter()
+        .map(|child| match child {
+            PrettyTree::Field(field) => Some((&*field.key, field.printed_value())).filter(|(_, x)| !x.contains('\n')),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Some((&branch.label, fields))
+}
@@ -0,0 +1,27 @@
+/// Strips module paths from a `std::any::type_name` string, keeping the
+/// generic structure: `std::collections::hash::map::HashMap<alloc::string::String, my_crate::Bar>`
+/// becomes `HashMap<String, Bar>`.
+pub fn short_type_name(name: &str) -> String {
+    let mut short = String::with_capacity(name.len());
+    let mut path = String::default();
+    let mut chars = name.chars().peekable();
+    while let Some(char) = chars.next() {
+        if char.is_alphanumeric() || char == '_' {
+            path.push(char);
+            continue
+        }
+        if char == ':' && chars.peek() == Some(&':') {
+            chars.next();
+            if path.is_empty() {
+                short.push_str("::");
+            }
+            path.clear();
+            continue
+        }
+        short.push_str(&path);
+        path.clear();
+        short.push(char);
+    }
+    short.push_str(&path);
+    short
+}
@@ -0,0 +1,23 @@
+use pretty_tree::*;
+
+fn user() -> PrettyTree<'static> {
+    PrettyTree::branch_of("User", [PrettyTree::key_value("name", PrettyTree::String("ann".into()))])
+}
+
+#[test]
+fn field_values_are_printed_quoted() {
+    assert_eq!(user().render(), "User\n╰╼\u{2009}name: \"ann\"");
+}
+
+#[test]
+fn field_values_are_exported_unquoted() {
+    let html = user().to_html();
+    assert!(html.contains("<span class=\"key\">name</span>: <span class=\"value\">ann</span>"), "{html}");
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn field_values_are_exported_as_json_unquoted() {
+    let json = serde_json::json!({"label": "User", "children": [{"key": "name", "value": "ann"}]});
+    assert_eq!(user().to_json(), json);
+}
@@ -0,0 +1,79 @@
+use pretty_tree::*;
+
+fn tree(label: &'static str) -> PrettyTree<'static> {
+    PrettyTree::branch_of(label, [PrettyTree::leaf("a"), PrettyTree::leaf("b")])
+}
+
+fn fragment(nodes: Vec<PrettyTree<'static>>) -> PrettyTree<'static> {
+    PrettyTree::Fragment(PrettyFragment::from_iter(nodes))
+}
+
+fn separated(separator: &'static str) -> Formatter {
+    Formatter::default().map_formatter_style(|x| x.forest_separator(Some(separator)))
+}
+
+#[test]
+fn forest() {
+    let forest = fragment(vec![tree("first"), tree("second"), PrettyTree::leaf("third")]);
+    assert_eq!(
+        forest.render(),
+        "first\n├╼\u{2009}a\n╰╼\u{2009}b\nsecond\n├╼\u{2009}a\n╰╼\u{2009}b\nthird",
+    );
+}
+
+#[test]
+fn separator() {
+    let forest = fragment(vec![PrettyTree::leaf("first"), tree("second")]);
+    assert_eq!(forest.format(&separated("")), "first\n\nsecond\n├╼\u{2009}a\n╰╼\u{2009}b");
+    assert_eq!(forest.format(&separated("---")), "first\n---\nsecond\n├╼\u{2009}a\n╰╼\u{2009}b");
+}
+
+#[test]
+fn one_tree() {
+    assert_eq!(fragment(vec![tree("only")]).render(), tree("only").render());
+    assert_eq!(fragment(vec![tree("only")]).format(&separated("---")), tree("only").render());
+}
+
+#[test]
+fn no_trees() {
+    assert_eq!(fragment(Vec::default()).render(), "");
+    assert_eq!(fragment(vec![fragment(Vec::default())]).format(&separated("---")), "");
+}
+
+#[test]
+fn nested_forests_are_spliced() {
+    let forest = fragment(vec![fragment(vec![tree("first"), tree("second")]), tree("third")]);
+    let flat = fragment(vec![tree("first"), tree("second"), tree("third")]);
+    assert_eq!(forest.render(), flat.render());
+}
+
+#[test]
+fn fragments_in_branches_are_spliced() {
+    let nested = PrettyTree::branch_of("parent", [
+        PrettyTree::leaf("before"),
+        fragment(vec![PrettyTree::leaf("spliced"), fragment(vec![PrettyTree::leaf("nested")])]),
+        fragment(Vec::default()),
+        PrettyTree::leaf("after"),
+    ]);
+    let flat = PrettyTree::branch_of("parent", ["before", "spliced", "nested", "after"].map(PrettyTree::leaf));
+    assert_eq!(nested.render(), flat.render());
+    assert_eq!(
+        nested.render(),
+        "parent\n├╼\u{2009}before\n├╼\u{2009}spliced\n├╼\u{2009}nested\n╰╼\u{2009}after",
+    );
+}
+
+#[test]
+fn fragments_in_lists_are_spliced() {
+    let list = PrettyList::from_iter(Some("Vec<i32>"), [
+        PrettyTree::value(1),
+        fragment(vec![PrettyTree::value(2), PrettyTree::value(3)]),
+        fragment(Vec::default()),
+    ]);
+    assert_eq!(
+        PrettyTree::from(list).render(),
+        "Vec<i32>\n├╼\u{2009}1\n├╼\u{2009}2\n╰╼\u{2009}3",
+    );
+    let single = PrettyList::from_iter(None::<&str>, [fragment(vec![PrettyTree::value(1)])]);
+    assert_eq!(PrettyTree::from(single).render(), "1");
+}
@@ -0,0 +1,22 @@
+use pretty_tree::*;
+
+fn paths(tree: &PrettyTree, style: FormatterStyle) -> String {
+    tree.format(&Formatter::new(style.gutter(Some(Gutter::Path))))
+}
+
+#[test]
+fn paths_follow_collapsed_repeats() {
+    let tree = PrettyTree::branch_of("Root", ["x", "x", "y"].map(PrettyTree::leaf));
+    let output = paths(&tree, FormatterStyle::default().collapse_repeats(true));
+    assert_eq!(output, "  0 │ Root\n0.0 │ ├╼\u{2009}x ×2\n0.2 │ ╰╼\u{2009}y");
+    assert_eq!(tree.get_by_path("0.2"), Some(PrettyTree::leaf("y")));
+}
+
+#[test]
+fn table_headers_and_cut_off_children_have_no_path() {
+    let record = |name: &'static str| PrettyTree::branch_of("User", [PrettyTree::key_value("name", PrettyTree::leaf(name))]);
+    let tree = PrettyTree::branch_of("Users", [record("ann"), record("bob"), record("cy")]);
+    let output = paths(&tree, FormatterStyle::default().tables(true).max_children(Some(2)));
+    assert_eq!(output, "  0 │ Users\n    │ ├╼\u{2009}name\n0.0 │ ├╼\u{2009}ann\n    │ ╰╼\u{2009}… 2 more");
+    assert_eq!(tree.get_by_path("0.0"), Some(record("ann")));
+}
@@ -0,0 +1,33 @@
+use pretty_tree::*;
+
+fn inline() -> FormatterStyle {
+    FormatterStyle::default().inline_width(Some(80))
+}
+
+#[test]
+fn inline_nodes() {
+    let add = PrettyTree::branch_of("Add", [PrettyTree::value(1), PrettyTree::value(2)]);
+    assert_eq!(add.format(&Formatter::new(inline())), "Add(1, 2)");
+}
+
+#[test]
+fn inline_nodes_keep_the_breadth_limit() {
+    let add = PrettyTree::branch_of("Add", (0..6).map(PrettyTree::value));
+    let formatter = Formatter::new(inline().max_children(Some(2)));
+    assert_eq!(add.format(&formatter), "Add(0, 1, … 4 more)");
+}
+
+#[test]
+fn inline_nodes_collapse_repeats() {
+    let add = PrettyTree::branch_of("Add", [1, 1, 1].map(PrettyTree::value));
+    let formatter = Formatter::new(inline().collapse_repeats(true));
+    assert_eq!(add.format(&formatter), "Add(1 ×3)");
+}
+
+#[test]
+fn tables_are_not_inlined() {
+    let record = |name: &str| PrettyTree::branch_of("User", [PrettyTree::key_value("name", PrettyTree::leaf(name.to_string()))]);
+    let users = PrettyTree::branch_of("Users", [record("ann"), record("bob")]);
+    let formatter = Formatter::new(inline().tables(true));
+    assert_eq!(users.format(&formatter), "Users\n├╼\u{2009}name\n├╼\u{2009}ann\n╰╼\u{2009}bob");
+}
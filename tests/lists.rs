@@ -0,0 +1,83 @@
+use pretty_tree::*;
+
+fn labeled(items: &[i32]) -> PrettyTree<'static> {
+    PrettyList::from_iter(Some("Vec<i32>"), items.iter().copied()).into()
+}
+
+fn unlabeled(items: &[i32]) -> PrettyTree<'static> {
+    PrettyList::from_iter(None::<&str>, items.iter().copied()).into()
+}
+
+/// Renders `list` on its own and as the first child of a branch.
+fn render(list: PrettyTree<'static>, compact_mode: bool) -> (String, String) {
+    let formatter = Formatter::default().map_formatter_style(|x| x.compact_mode(compact_mode));
+    let nested = PrettyTree::branch_of("Parent", [list.clone(), PrettyTree::leaf("sibling")]);
+    (list.format(&formatter), nested.format(&formatter))
+}
+
+fn nested(lines: &str) -> String {
+    format!("Parent\n{lines}\n╰╼\u{2009}sibling")
+}
+
+#[test]
+fn labeled_empty() {
+    assert_eq!(render(labeled(&[]), false), ("Vec<i32>: []".into(), nested("├╼\u{2009}Vec<i32>: []")));
+    assert_eq!(render(labeled(&[]), true), ("[]".into(), nested("├╼\u{2009}[]")));
+}
+
+#[test]
+fn labeled_one() {
+    assert_eq!(render(labeled(&[1]), false), ("Vec<i32>\n╰╼\u{2009}1".into(), nested("├╼\u{2009}Vec<i32>\n│  ╰╼\u{2009}1")));
+    assert_eq!(render(labeled(&[1]), true), ("1".into(), nested("├╼\u{2009}1")));
+}
+
+#[test]
+fn labeled_many() {
+    assert_eq!(
+        render(labeled(&[1, 2, 3]), false),
+        ("Vec<i32>\n├╼\u{2009}1\n├╼\u{2009}2\n╰╼\u{2009}3".into(), nested("├╼\u{2009}Vec<i32>\n│  ├╼\u{2009}1\n│  ├╼\u{2009}2\n│  ╰╼\u{2009}3")),
+    );
+    assert_eq!(
+        render(labeled(&[1, 2, 3]), true),
+        ("╭╼\u{2009}1\n├╼\u{2009}2\n╰╼\u{2009}3".into(), nested("├  ╭╼\u{2009}1\n│  ├╼\u{2009}2\n│  ╰╼\u{2009}3")),
+    );
+}
+
+#[test]
+fn unlabeled_empty() {
+    for compact_mode in [false, true] {
+        assert_eq!(render(unlabeled(&[]), compact_mode), ("[]".into(), nested("├╼\u{2009}[]")));
+    }
+}
+
+#[test]
+fn unlabeled_one() {
+    for compact_mode in [false, true] {
+        assert_eq!(render(unlabeled(&[1]), compact_mode), ("1".into(), nested("├╼\u{2009}1")));
+    }
+}
+
+#[test]
+fn unlabeled_many() {
+    for compact_mode in [false, true] {
+        assert_eq!(
+            render(unlabeled(&[1, 2, 3]), compact_mode),
+            ("╭╼\u{2009}1\n├╼\u{2009}2\n╰╼\u{2009}3".into(), nested("├  ╭╼\u{2009}1\n│  ├╼\u{2009}2\n│  ╰╼\u{2009}3")),
+        );
+    }
+}
+
+#[test]
+fn nested_brackets() {
+    let list = PrettyList::from_iter(None::<&str>, [unlabeled(&[1, 2]), PrettyTree::value(3)]);
+    let formatter = Formatter::default().map_formatter_style(|x| x.compact_mode(true));
+    assert_eq!(PrettyTree::from(list).format(&formatter), "╭  ╭╼\u{2009}1\n│  ╰╼\u{2009}2\n╰╼\u{2009}3");
+}
+
+#[test]
+fn compact_mode_folds_chains() {
+    let tree = PrettyTree::branch_of("Expr", [PrettyTree::branch_of("Paren", [PrettyTree::leaf("5")])]);
+    assert_eq!(tree.render(), "Expr\n╰╼\u{2009}Paren\n   ╰╼\u{2009}5");
+    let formatter = Formatter::default().map_formatter_style(|x| x.compact_mode(true));
+    assert_eq!(tree.format(&formatter), "Expr › Paren › 5");
+}
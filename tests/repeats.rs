@@ -0,0 +1,40 @@
+use pretty_tree::*;
+
+fn node(label: &'static str) -> PrettyTree<'static> {
+    PrettyTree::branch_of(label, [PrettyTree::leaf("x")])
+}
+
+#[test]
+fn back_references_use_printed_positions() {
+    let tree = PrettyTree::branch_of("Root", ["A", "A", "A", "B", "C", "B"].map(node));
+    let style = FormatterStyle::default().collapse_repeats(true).back_references(true);
+    let output = tree.format(&Formatter::new(style));
+    let expected = [
+        "Root",
+        "├╼\u{2009}A ×3",
+        "│  ╰╼\u{2009}x",
+        "├╼\u{2009}B",
+        "│  ╰╼\u{2009}x",
+        "├╼\u{2009}C",
+        "│  ╰╼\u{2009}x",
+        "╰╼\u{2009}B (same as #1)",
+    ];
+    assert_eq!(output, expected.join("\n"));
+}
+
+#[test]
+fn back_references_to_lists() {
+    let rows = vec![vec![1u8, 2], vec![3], vec![1, 2]];
+    let style = FormatterStyle::default().back_references(true);
+    let output = rows.to_pretty_tree().format(&Formatter::new(style));
+    let expected = [
+        "Vec<Vec<u8>>",
+        "├╼\u{2009}Vec<u8>",
+        "│  ├╼\u{2009}1",
+        "│  ╰╼\u{2009}2",
+        "├╼\u{2009}Vec<u8>",
+        "│  ╰╼\u{2009}3",
+        "╰╼\u{2009}Vec<u8> (same as #0)",
+    ];
+    assert_eq!(output, expected.join("\n"));
+}
@@ -0,0 +1,27 @@
+use std::collections::HashSet;
+
+use pretty_tree::*;
+
+#[test]
+fn digits_compare_as_numbers() {
+    let set = ["k10", "k2", "k1"].into_iter().collect::<HashSet<_>>();
+    let formatter = Formatter::default().map_formatter_style(|x| x.compact_mode(true));
+    assert_eq!(set.to_pretty_tree().format(&formatter), "╭╼\u{2009}\"k1\"\n├╼\u{2009}\"k2\"\n╰╼\u{2009}\"k10\"");
+}
+
+#[test]
+fn leading_zeros_break_ties() {
+    let set = ["k7", "k007", "k07"].into_iter().collect::<HashSet<_>>();
+    let sorted = set.to_pretty_tree().sort_key();
+    assert_eq!(sorted, r#""k007", "k07", "k7""#);
+}
+
+#[test]
+fn shared_cycles_in_unordered_lists() {
+    let entry = PrettyShared::new(PrettyTree::Empty);
+    let list = PrettyList::from_iter(None::<&str>, [PrettyTree::leaf("a"), PrettyTree::Shared(entry.clone())]);
+    entry.set(PrettyTree::branch_of("entry", [PrettyTree::List(list.unordered(true))]));
+    let tree = PrettyTree::Shared(entry);
+    assert_eq!(tree.sort_key(), "entry(a, ↑ ref)");
+    tree.format(&Formatter::default());
+}